@@ -0,0 +1,37 @@
+use crate::{datasets::prices::Prices, files::versioned::Versioned};
+use logger::log_info;
+use serde_derive::{Deserialize, Serialize};
+
+/// A crafting action to take next on a current item, with the expected
+/// remaining cost and chance of attaining the target after taking it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionRecommendation {
+    pub action: String,
+    pub success_chance: f32,
+    pub expected_cost: f32,
+}
+
+impl ActionRecommendation {
+    /// Gets the expected cost of each success, which is infinite if the
+    /// target is never attained.
+    pub fn get_cost_per_success(&self) -> f32 {
+        if self.success_chance > 0.0 {
+            self.expected_cost / self.success_chance
+        } else {
+            f32::INFINITY
+        }
+    }
+
+    /// Prints a user-friendly representation of a recommendation, with costs
+    /// reported in `currency`.
+    pub fn display(&self, prices: &Prices, currency: &str) {
+        log_info!(
+            "use '{}' next: {:.2}% chance to reach the target, with an expected remaining cost of {}.",
+            self.action,
+            self.success_chance * 100.0,
+            prices.format_cost(self.expected_cost, currency)
+        );
+    }
+}
+
+impl Versioned for ActionRecommendation {}
@@ -0,0 +1,245 @@
+use crate::{
+    crafting::compiled_affix::CompiledAffix,
+    items::{interned_id::InternedId, modifier::Modifier},
+    random::alias_table::AliasTable,
+};
+use rand::Rng;
+
+/// The number of times an excluded affix is rerolled before the remaining
+/// affixes are weighed directly instead.
+const MAX_EXCLUDED_REROLLS: u8 = 16;
+
+/// The affixes that can roll at an item level up to the next bracket, and
+/// their weighted table.
+#[derive(Clone)]
+struct AffixBracket {
+    /// The lowest item level the bracket applies to.
+    item_level: u8,
+    /// The index of every affix that can roll in the bracket.
+    affix_indices: Vec<usize>,
+    affix_table: AliasTable,
+}
+
+/// The affixes that can roll on one side of an item class, with a weighted
+/// table for every item level that unlocks more affixes or tiers.
+#[derive(Clone, Default)]
+pub struct AffixTable {
+    affixes: Vec<CompiledAffix>,
+    brackets: Vec<AffixBracket>,
+}
+
+impl AffixTable {
+    /// Makes a new [`AffixTable`] from `affixes`.
+    pub fn new(affixes: Vec<CompiledAffix>) -> Self {
+        let mut item_levels = affixes
+            .iter()
+            .flat_map(|a| a.get_bracket_item_levels())
+            .collect::<Vec<u8>>();
+        item_levels.sort();
+        item_levels.dedup();
+
+        let brackets = item_levels
+            .into_iter()
+            .filter_map(|item_level| {
+                let affix_indices = (0..affixes.len())
+                    .filter(|i| affixes[*i].get_weight(item_level) > 0)
+                    .collect::<Vec<usize>>();
+                let weights = affix_indices
+                    .iter()
+                    .map(|i| affixes[*i].get_weight(item_level))
+                    .collect::<Vec<u32>>();
+                Some(AffixBracket {
+                    item_level,
+                    affix_table: AliasTable::new(&weights)?,
+                    affix_indices,
+                })
+            })
+            .collect();
+
+        Self { affixes, brackets }
+    }
+
+    /// Gets the affixes that can roll at `item_level`.
+    fn get_bracket(&self, item_level: u8) -> Option<&AffixBracket> {
+        let bracket = self
+            .brackets
+            .partition_point(|b| b.item_level <= item_level);
+        self.brackets.get(bracket.checked_sub(1)?)
+    }
+
+    /// Gets every affix that can roll at `item_level`.
+    pub fn get_affixes(&self, item_level: u8) -> Vec<&CompiledAffix> {
+        self.get_bracket(item_level)
+            .into_iter()
+            .flat_map(|b| b.affix_indices.iter().map(|i| &self.affixes[*i]))
+            .collect()
+    }
+
+    /// Rolls a random affix at `item_level` based on its weight, skipping
+    /// any affixes in `excluded_ids`, along with its interned id.
+    pub fn choose_random_affix<R: Rng>(
+        &self,
+        item_level: u8,
+        excluded_ids: &[InternedId],
+        rng: &mut R,
+    ) -> Option<(InternedId, Modifier)> {
+        let bracket = self.get_bracket(item_level)?;
+
+        // items only hold a few affixes, so rerolling them is usually enough
+        for _ in 0..MAX_EXCLUDED_REROLLS {
+            let affix = &self.affixes[bracket.affix_indices[bracket.affix_table.sample(rng)]];
+            if !excluded_ids.contains(&affix.affix_id) {
+                return Some((affix.affix_id, affix.roll(item_level, rng)?));
+            }
+        }
+
+        let valid_affixes = bracket
+            .affix_indices
+            .iter()
+            .map(|i| &self.affixes[*i])
+            .filter(|a| !excluded_ids.contains(&a.affix_id))
+            .collect::<Vec<&CompiledAffix>>();
+        let total_weight = valid_affixes
+            .iter()
+            .map(|a| a.get_weight(item_level))
+            .sum::<u32>();
+        if total_weight == 0 {
+            return None;
+        }
+        let mut chosen_weight = rng.random_range(0..total_weight);
+        valid_affixes
+            .into_iter()
+            .find(|a| {
+                let weight = a.get_weight(item_level);
+                let is_chosen = chosen_weight < weight;
+                chosen_weight = chosen_weight.saturating_sub(weight);
+                is_chosen
+            })
+            .and_then(|a| Some((a.affix_id, a.roll(item_level, rng)?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::{affix::Affix, affix_tier::AffixTier, modifier_tier::ModifierTier};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    const SAMPLES: u32 = 100_000;
+
+    /// Makes an affix from `(value, item_level, weight)` tiers, best first.
+    fn affix(id: &str, tiers: &[(u16, u8, u16)]) -> CompiledAffix {
+        let affix_tier = AffixTier {
+            affix: id.to_owned(),
+            tiers: tiers
+                .iter()
+                .map(|(value, item_level, weight)| ModifierTier {
+                    value: Some(*value),
+                    item_level: *item_level,
+                    weight: *weight,
+                    ..Default::default()
+                })
+                .collect(),
+        };
+        let affix = Affix {
+            name: format!("# {id}"),
+            id: id.to_owned(),
+            tags: None,
+        };
+        CompiledAffix::new(&affix_tier, &affix, InternedId(id.parse().unwrap())).unwrap()
+    }
+
+    fn count_rolls(
+        table: &AffixTable,
+        item_level: u8,
+        excluded_ids: &[InternedId],
+    ) -> Vec<(u32, u32)> {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut counts = vec![];
+        for _ in 0..SAMPLES {
+            let (affix_id, modifier) = table
+                .choose_random_affix(item_level, excluded_ids, &mut rng)
+                .unwrap();
+            assert_eq!(modifier.id, affix_id.0.to_string());
+            let id = affix_id.0 as u32;
+            match counts.iter_mut().find(|(i, _)| *i == id) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((id, 1)),
+            }
+        }
+        counts.sort();
+        counts
+    }
+
+    fn assert_share(counts: &[(u32, u32)], id: u32, expected: f64) {
+        let count = counts.iter().find(|(i, _)| *i == id).map_or(0, |(_, c)| *c);
+        let share = count as f64 / SAMPLES as f64;
+        assert!(
+            (share - expected).abs() < 0.01,
+            "affix {id} rolled {share}, expected {expected}"
+        );
+    }
+
+    #[test]
+    fn only_rolls_tiers_unlocked_at_the_item_level() {
+        let table = AffixTable::new(vec![affix(
+            "1",
+            &[(3, 82, 1000), (2, 60, 1000), (1, 1, 1000)],
+        )]);
+        let mut rng = StdRng::seed_from_u64(3);
+
+        for _ in 0..1000 {
+            let (_, modifier) = table.choose_random_affix(65, &[], &mut rng).unwrap();
+            assert_ne!(modifier.tier, 1);
+            assert_ne!(modifier.value, 3);
+        }
+        assert!(table.choose_random_affix(0, &[], &mut rng).is_none());
+    }
+
+    #[test]
+    fn weighs_affixes_by_their_unlocked_tiers() {
+        let table = AffixTable::new(vec![
+            affix("1", &[(2, 82, 3000), (1, 1, 1000)]),
+            affix("2", &[(1, 1, 1000)]),
+        ]);
+
+        let counts = count_rolls(&table, 65, &[]);
+        assert_share(&counts, 1, 0.5);
+        assert_share(&counts, 2, 0.5);
+
+        let counts = count_rolls(&table, 82, &[]);
+        assert_share(&counts, 1, 0.8);
+        assert_share(&counts, 2, 0.2);
+    }
+
+    #[test]
+    fn excluding_affixes_keeps_the_remaining_distribution() {
+        let table = AffixTable::new(vec![
+            affix("1", &[(1, 1, 100)]),
+            affix("2", &[(1, 1, 200)]),
+            affix("3", &[(1, 1, 300)]),
+            affix("4", &[(1, 1, 400)]),
+        ]);
+
+        let counts = count_rolls(&table, 82, &[InternedId(3)]);
+        assert_share(&counts, 1, 100.0 / 700.0);
+        assert_share(&counts, 2, 200.0 / 700.0);
+        assert_share(&counts, 3, 0.0);
+        assert_share(&counts, 4, 400.0 / 700.0);
+    }
+
+    #[test]
+    fn excluding_common_affixes_falls_back_to_the_remaining_distribution() {
+        // the excluded affix is rolled nearly every time, so every reroll fails
+        let table = AffixTable::new(vec![
+            affix("1", &[(1, 1, 1)]),
+            affix("2", &[(1, 1, 3)]),
+            affix("3", &[(1, 1, 60_000)]),
+        ]);
+
+        let counts = count_rolls(&table, 82, &[InternedId(3)]);
+        assert_share(&counts, 1, 0.25);
+        assert_share(&counts, 2, 0.75);
+        assert_share(&counts, 3, 0.0);
+    }
+}
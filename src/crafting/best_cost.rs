@@ -0,0 +1,30 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// The lowest cost found so far, shared between threads without locking.
+pub struct BestCost {
+    bits: AtomicU32,
+}
+
+impl Default for BestCost {
+    fn default() -> Self {
+        Self {
+            bits: AtomicU32::new(f32::INFINITY.to_bits()),
+        }
+    }
+}
+
+impl BestCost {
+    /// Gets the lowest cost so far, which is infinite if there is none.
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.bits.load(Ordering::Relaxed))
+    }
+
+    /// Replaces the lowest cost with `cost` if it's lower, and returns if it was.
+    pub fn try_improve(&self, cost: f32) -> bool {
+        self.bits
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                (cost < f32::from_bits(bits)).then_some(cost.to_bits())
+            })
+            .is_ok()
+    }
+}
@@ -0,0 +1,36 @@
+use crate::{
+    crafting::cost_distribution::CostDistribution, datasets::prices::Prices,
+    files::versioned::Versioned,
+};
+use logger::log_info;
+use serde_derive::{Deserialize, Serialize};
+
+/// The best crafting strategy found for attaining a target within a budget.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetReport {
+    pub budget: f32,
+    pub actions: Vec<String>,
+    pub success_chance: f32,
+    pub cost_distribution: CostDistribution,
+}
+
+impl BudgetReport {
+    /// Prints a user-friendly representation of a budget report, with costs
+    /// reported in `currency`.
+    pub fn display(&self, prices: &Prices, currency: &str) {
+        log_info!(
+            "{:.2}% chance to reach the target within {}.",
+            self.success_chance * 100.0,
+            prices.format_cost(self.budget, currency)
+        );
+        log_info!("  using actions: {:?}", self.actions);
+        for percentile in [50.0, 90.0, 99.0] {
+            log_info!(
+                "  p{percentile} net cost: {}",
+                prices.format_cost(self.cost_distribution.get_percentile(percentile), currency)
+            );
+        }
+    }
+}
+
+impl Versioned for BudgetReport {}
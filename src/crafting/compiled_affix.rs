@@ -0,0 +1,114 @@
+use crate::{
+    datasets::{affix::Affix, affix_tier::AffixTier, modifier_tier::ModifierTier},
+    items::{interned_id::InternedId, modifier::Modifier},
+    random::alias_table::AliasTable,
+};
+use rand::Rng;
+
+/// The tiers of an affix that can roll from an item level up to the next
+/// bracket, with their weights prepared ahead of time.
+#[derive(Clone)]
+struct TierBracket {
+    /// The lowest item level the bracket applies to.
+    item_level: u8,
+    /// The weight of every tier in the bracket added together.
+    weight: u32,
+    /// The index of every tier in the bracket.
+    tier_indices: Vec<usize>,
+    tier_table: AliasTable,
+}
+
+/// An affix that can roll on an item class, with everything needed to roll
+/// it prepared ahead of time.
+#[derive(Clone)]
+pub struct CompiledAffix {
+    pub id: String,
+    pub affix_id: InternedId,
+    /// The modifier name, with "#" in place of the rolled value.
+    pub name: String,
+    pub tiers: Vec<ModifierTier>,
+    /// The lowest item level that any tier can roll at.
+    pub minimum_item_level: u8,
+    /// The tiers that can roll at each item level a tier unlocks at.
+    brackets: Vec<TierBracket>,
+}
+
+impl CompiledAffix {
+    /// Makes a new [`CompiledAffix`] from `affix_tier`, named by `affix` and
+    /// interned as `affix_id`, or `None` if it has no tiers to roll.
+    pub fn new(affix_tier: &AffixTier, affix: &Affix, affix_id: InternedId) -> Option<Self> {
+        let mut item_levels = affix_tier
+            .tiers
+            .iter()
+            .map(|t| t.item_level)
+            .collect::<Vec<u8>>();
+        item_levels.sort();
+        item_levels.dedup();
+
+        let brackets = item_levels
+            .into_iter()
+            .filter_map(|item_level| {
+                let tier_indices = (0..affix_tier.tiers.len())
+                    .filter(|i| affix_tier.tiers[*i].item_level <= item_level)
+                    .collect::<Vec<usize>>();
+                let tier_weights = tier_indices
+                    .iter()
+                    .map(|i| affix_tier.tiers[*i].weight as u32)
+                    .collect::<Vec<u32>>();
+                Some(TierBracket {
+                    item_level,
+                    weight: tier_weights.iter().sum(),
+                    tier_table: AliasTable::new(&tier_weights)?,
+                    tier_indices,
+                })
+            })
+            .collect::<Vec<TierBracket>>();
+
+        Some(Self {
+            id: affix.id.clone(),
+            affix_id,
+            name: affix.name.clone(),
+            tiers: affix_tier.tiers.clone(),
+            minimum_item_level: brackets.first()?.item_level,
+            brackets,
+        })
+    }
+
+    /// Gets every item level that unlocks more tiers of the affix.
+    pub fn get_bracket_item_levels(&self) -> impl Iterator<Item = u8> + '_ {
+        self.brackets.iter().map(|b| b.item_level)
+    }
+
+    /// Gets the tiers that can roll at `item_level`.
+    fn get_bracket(&self, item_level: u8) -> Option<&TierBracket> {
+        let bracket = self
+            .brackets
+            .partition_point(|b| b.item_level <= item_level);
+        self.brackets.get(bracket.checked_sub(1)?)
+    }
+
+    /// Gets the weight of every tier that can roll at `item_level` added
+    /// together, defaults to `0`.
+    pub fn get_weight(&self, item_level: u8) -> u32 {
+        self.get_bracket(item_level).map_or(0, |b| b.weight)
+    }
+
+    /// Rolls a random tier that can roll at `item_level` and its value as a
+    /// [`Modifier`], or `None` if no tier can roll.
+    pub fn roll<R: Rng>(&self, item_level: u8, rng: &mut R) -> Option<Modifier> {
+        let bracket = self.get_bracket(item_level)?;
+        let tier_index = bracket.tier_indices[bracket.tier_table.sample(rng)];
+        let tier = &self.tiers[tier_index];
+        let value = tier.get_value();
+
+        Some(Modifier {
+            name: self.name.replace("#", &value.to_string()),
+            id: self.id.clone(),
+            tier: (tier_index + 1) as u8,
+            value,
+            weight: tier.weight,
+            desecrated: false,
+            fractured: false,
+        })
+    }
+}
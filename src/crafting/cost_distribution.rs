@@ -0,0 +1,145 @@
+use crate::files::versioned::Versioned;
+use rand::{Rng, seq::IndexedRandom};
+use serde_derive::{Deserialize, Serialize};
+
+/// The distribution of costs spent attaining a target, over many simulated
+/// crafting sessions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CostDistribution {
+    pub costs: Vec<f32>,
+}
+
+impl CostDistribution {
+    /// Makes a new [`CostDistribution`] from unsorted `costs`.
+    pub fn from_costs(mut costs: Vec<f32>) -> Self {
+        costs.sort_by(|a, b| a.total_cmp(b));
+        Self { costs }
+    }
+
+    /// Makes a new [`CostDistribution`] from the `(cost, succeeded)` results of
+    /// independent crafting runs, where failed runs are retried until one
+    /// succeeds, so each cost is the total spent over a series of runs.
+    ///
+    /// Failed runs after the last success are an unfinished series, which
+    /// is finished with the average cost of a whole series, since every
+    /// retry starts over.
+    pub fn from_run_costs(run_costs: &[(f32, bool)]) -> Self {
+        let mut costs = Vec::new();
+        let mut spent = 0.0;
+        for (cost, succeeded) in run_costs {
+            spent += cost;
+            if *succeeded {
+                costs.push(spent);
+                spent = 0.0;
+            }
+        }
+        let has_unfinished_series = run_costs.last().is_some_and(|(_, succeeded)| !succeeded);
+        if has_unfinished_series && !costs.is_empty() {
+            let average_cost = costs.iter().sum::<f32>() / costs.len() as f32;
+            costs.push(spent + average_cost);
+        }
+        Self::from_costs(costs)
+    }
+
+    /// Makes a new [`CostDistribution`] of recombining two items crafted with
+    /// the `left` and `right` costs, where both items are crafted again until
+    /// recombining succeeds with `success_chance`, over `samples`.
+    pub fn from_recombining(
+        left: &CostDistribution,
+        right: &CostDistribution,
+        success_chance: f32,
+        samples: u32,
+    ) -> Self {
+        if success_chance <= 0.0 || left.costs.is_empty() || right.costs.is_empty() {
+            return Self::default();
+        }
+
+        let mut rng = rand::rng();
+        let costs = (0..samples)
+            .map(|_| {
+                let mut spent = 0.0;
+                loop {
+                    spent += left.sample(&mut rng).unwrap_or_default()
+                        + right.sample(&mut rng).unwrap_or_default();
+                    if rng.random_bool(success_chance.min(1.0) as f64) {
+                        break spent;
+                    }
+                }
+            })
+            .collect();
+        Self::from_costs(costs)
+    }
+
+    /// Gets the average cost, defaults to `0.0` without any costs.
+    pub fn get_mean(&self) -> f32 {
+        if self.costs.is_empty() {
+            0.0
+        } else {
+            self.costs.iter().sum::<f32>() / self.costs.len() as f32
+        }
+    }
+
+    /// Gets the variance of the costs, defaults to `0.0` without any costs.
+    pub fn get_variance(&self) -> f32 {
+        if self.costs.is_empty() {
+            return 0.0;
+        }
+        let mean = self.get_mean();
+        self.costs.iter().map(|c| (c - mean).powi(2)).sum::<f32>() / self.costs.len() as f32
+    }
+
+    /// Gets the standard deviation of the costs.
+    pub fn get_standard_deviation(&self) -> f32 {
+        self.get_variance().sqrt()
+    }
+
+    /// Gets the highest cost, defaults to `0.0` without any costs.
+    pub fn get_worst_case(&self) -> f32 {
+        self.costs.last().copied().unwrap_or_default()
+    }
+
+    /// Gets a random cost from the distribution, if there are any.
+    pub fn sample(&self, rng: &mut impl Rng) -> Option<f32> {
+        self.costs.choose(rng).copied()
+    }
+
+    /// Gets the cost at `percentile` (from `0.0` to `100.0`), defaults to
+    /// `0.0` without any costs.
+    pub fn get_percentile(&self, percentile: f32) -> f32 {
+        if self.costs.is_empty() {
+            return 0.0;
+        }
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * self.costs.len() as f32).ceil() as usize;
+        self.costs[rank.saturating_sub(1).min(self.costs.len() - 1)]
+    }
+
+    /// Gets the chance (from `0.0` to `1.0`) that the cost exceeds `threshold`.
+    pub fn get_exceed_chance(&self, threshold: f32) -> f32 {
+        if self.costs.is_empty() {
+            return 0.0;
+        }
+        self.costs.iter().filter(|c| **c > threshold).count() as f32 / self.costs.len() as f32
+    }
+}
+
+impl Versioned for CostDistribution {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_costs_keep_failed_runs_after_the_last_success() {
+        let distribution =
+            CostDistribution::from_run_costs(&[(1.0, false), (1.0, true), (3.0, false)]);
+
+        assert_eq!(distribution.costs, vec![2.0, 5.0]);
+    }
+
+    #[test]
+    fn run_costs_without_successes_are_empty() {
+        let distribution = CostDistribution::from_run_costs(&[(1.0, false), (2.0, false)]);
+
+        assert!(distribution.costs.is_empty());
+    }
+}
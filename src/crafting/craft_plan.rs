@@ -0,0 +1,78 @@
+use crate::{
+    crafting::{cost_distribution::CostDistribution, craft_step::CraftStep},
+    datasets::prices::Prices,
+    files::versioned::Versioned,
+    items::item_state::ItemState,
+};
+use logger::log_info;
+use serde_derive::{Deserialize, Serialize};
+
+/// A plan for crafting a `target` item, as a tree of [`CraftStep`]s.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CraftPlan {
+    pub target: ItemState,
+    pub step: CraftStep,
+    pub success_chance: f32,
+    #[serde(with = "crate::files::infinite_float")]
+    pub expected_cost: f32,
+    pub cost_distribution: CostDistribution,
+}
+
+impl CraftPlan {
+    /// Determines if the plan can reach its target at all.
+    pub fn is_possible(&self) -> bool {
+        self.expected_cost.is_finite()
+    }
+
+    /// Prints a user-friendly representation of a craft plan, with costs
+    /// reported in `currency`.
+    pub fn display(&self, prices: &Prices, currency: &str) {
+        self.display_step(prices, currency, 0);
+    }
+
+    /// Prints a user-friendly representation of a craft plan step, indented
+    /// by `depth`.
+    fn display_step(&self, prices: &Prices, currency: &str, depth: usize) {
+        let indent = "  ".repeat(depth);
+        let target_mods = self
+            .target
+            .prefixes
+            .iter()
+            .chain(self.target.suffixes.iter())
+            .map(|m| m.id.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ");
+
+        let p90_cost = self.cost_distribution.get_percentile(90.0);
+        match &self.step {
+            CraftStep::Craft { start, actions } => {
+                log_info!(
+                    "{indent}- craft [{target_mods}] (net cost: {}, p90: {}, {:.2}% per attempt)",
+                    prices.format_cost(self.expected_cost, currency),
+                    prices.format_cost(p90_cost, currency),
+                    self.success_chance * 100.0
+                );
+                if start.price > 0.0 {
+                    log_info!(
+                        "{indent}  starting from: {} (bought for {})",
+                        start.describe(),
+                        prices.format_cost(start.price, currency)
+                    );
+                }
+                log_info!("{indent}  sequence: {:?}", actions);
+            }
+            CraftStep::Recombine { left, right, .. } => {
+                log_info!(
+                    "{indent}- recombine into [{target_mods}] (net cost: {}, p90: {}, {:.2}% per attempt)",
+                    prices.format_cost(self.expected_cost, currency),
+                    prices.format_cost(p90_cost, currency),
+                    self.success_chance * 100.0
+                );
+                left.display_step(prices, currency, depth + 1);
+                right.display_step(prices, currency, depth + 1);
+            }
+        }
+    }
+}
+
+impl Versioned for CraftPlan {}
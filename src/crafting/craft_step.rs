@@ -0,0 +1,21 @@
+use crate::{
+    crafting::{craft_plan::CraftPlan, starting_item::StartingItem},
+    items::modifier::Modifier,
+};
+use serde_derive::{Deserialize, Serialize};
+
+/// A step of a [`CraftPlan`], either crafting a starting item with currency or
+/// crafting two half-items and recombining them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CraftStep {
+    Craft {
+        start: StartingItem,
+        actions: Vec<String>,
+    },
+    Recombine {
+        left: Box<CraftPlan>,
+        right: Box<CraftPlan>,
+        left_mods: Vec<Modifier>,
+        right_mods: Vec<Modifier>,
+    },
+}
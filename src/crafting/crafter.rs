@@ -1,179 +1,62 @@
 use crate::{
-    crafting::affix_candidate::AffixCandidate,
+    crafting::crafting_index::CraftingIndex,
     datasets::{
-        affix_tier::AffixTier, class_tier::ClassTier, craft_action::CraftAction,
-        craft_outcome::CraftOutcome, item::Item, modifiers::Modifiers,
+        class_tier::ClassTier, craft_action::CraftAction, craft_outcome::CraftOutcome,
+        items::Items, outcome_action::OutcomeAction, outcome_affix::OutcomeAffix,
+    },
+    items::{
+        affix_side::AffixSide, id_registry::IdRegistry, item_state::ItemState, modifier::Modifier,
     },
-    items::{item_state::ItemState, modifier::Modifier},
 };
 use logger::log_debug;
-use rand::{
-    Rng,
-    distr::{Distribution, weighted::WeightedIndex},
-};
+use rand::{Rng, seq::IndexedRandom};
 
 /// Responsible for all affix-related actions.
 pub trait Crafter {
-    /// Gets a list of all possible affixes (prefixes or suffixes) that
-    /// can roll on an item of a given class and level.
-    fn get_possible_affixes(
-        &self,
-        class_tiers: &[ClassTier],
-        item_class: &str,
-        item_level: u8,
-        affix_type: &str,
-    ) -> Vec<AffixCandidate> {
-        if let Some(class_tiers) = class_tiers
-            .iter()
-            .find(|ct| ct.classes.contains(&item_class.to_string()))
-        {
-            match affix_type {
-                "prefix" => &class_tiers.prefixes,
-                _ => &class_tiers.suffixes,
-            }
-            .iter()
-            .filter(|affix_tier| {
-                affix_tier
-                    .tiers
-                    .iter()
-                    .any(|tier| tier.item_level <= item_level)
-            })
-            .flat_map(|affix_tier: &AffixTier| {
-                affix_tier
-                    .tiers
-                    .clone()
-                    .iter()
-                    .map(|tier| AffixCandidate {
-                        affix: affix_tier.clone().affix,
-                        affix_tier: affix_tier.clone(),
-                        weight: tier.weight,
-                    })
-                    .collect::<Vec<AffixCandidate>>()
-            })
-            .collect::<Vec<AffixCandidate>>()
-        } else {
-            vec![]
-        }
-    }
-
-    /// Selects a random affix based on its weight.
-    fn choose_random_affix(
-        &self,
-        modifiers: Modifiers,
-        current_affixes: &[Modifier],
-        possible_affixes: &[AffixCandidate],
-    ) -> Option<Modifier> {
-        if possible_affixes.is_empty() {
-            log_debug!("No possible affixes when choosing random modifier.");
-            return None;
-        }
-
-        let current_affix_ids: Vec<&str> =
-            current_affixes.iter().map(|ca| ca.id.as_str()).collect();
-
-        let valid_affixes: Vec<&AffixCandidate> = possible_affixes
-            .iter()
-            .filter(|t| !current_affix_ids.contains(&t.affix.as_str()))
-            .collect();
-
-        let weights: Vec<u64> = valid_affixes
-            .clone()
-            .into_iter()
-            .map(|t| t.weight.into())
-            .collect();
-
-        let dist = WeightedIndex::new(weights)
-            .expect("Could not make a new weighted index for affix weights.");
-        let mut rng = rand::rng();
-        let chosen_index = dist.sample(&mut rng);
-        let chosen_affix_candidate = &valid_affixes[chosen_index];
-
-        let chosen_modifier_from_db = modifiers
-            .get_affix_by_id(&chosen_affix_candidate.affix)
-            .unwrap();
-
-        let tier_weights: Vec<u16> = chosen_affix_candidate
-            .affix_tier
-            .tiers
-            .iter()
-            .map(|t| t.weight)
-            .collect();
-        let tier_dist = WeightedIndex::new(tier_weights)
-            .expect("Could not make a new weighted index for tier weights.");
-        let chosen_tier_index = tier_dist.sample(&mut rng);
-        let chosen_tier = &chosen_affix_candidate.affix_tier.tiers[chosen_tier_index];
-
-        let chosen_tier_value = chosen_tier.get_value();
-
-        Some(Modifier {
-            // Take ownership of the new String created by .replace()
-            name: chosen_modifier_from_db
-                .name
-                .replace("#", &chosen_tier_value.to_string()),
-            // Clone the ID to move an owned String into the struct
-            id: chosen_modifier_from_db.id.clone(),
-            tier: (chosen_tier_index + 1) as u8,
-            value: chosen_tier_value as u16,
-            weight: chosen_tier.weight,
-        })
-    }
-
     /// Adds a random affix to `item_state`.
     fn add_random_affix(
         &self,
-        items: &[Item],
-        class_tiers: &[ClassTier],
-        modifiers: &Modifiers,
+        items: &Items,
+        crafting_index: &CraftingIndex,
+        id_registry: &IdRegistry,
         item_state: &mut ItemState,
-        affix_type: &str,
+        affix_side: AffixSide,
         count: i32,
     ) -> bool {
-        let current_affixes = [item_state.prefixes.clone(), item_state.suffixes.clone()].concat();
-
-        let affix_list = match affix_type {
-            "prefix" => &mut item_state.prefixes,
-            "suffix" => &mut item_state.suffixes,
-            _ => {
-                log_debug!("Error: Invalid affix type");
-                return false;
-            }
+        let mut current_affix_ids = id_registry.get_affix_ids(item_state);
+        let unrevealed_affixes = item_state.get_unrevealed_count(affix_side);
+        let max_affixes = items
+            .get_affix_slots(&item_state.base, item_state.rarity)
+            .get_max(affix_side);
+        let affix_list = match affix_side {
+            AffixSide::Prefix => &mut item_state.prefixes,
+            AffixSide::Suffix => &mut item_state.suffixes,
         };
 
-        let matched_item = items
-            .iter()
-            .find(|i| i.name.eq(&item_state.base))
-            .expect("could not find matching item!");
-
-        let max_affixes = match affix_type {
-            "prefix" => item_state.max_prefixes,
-            "suffix" => item_state.max_suffixes,
-            _ => 3,
+        let Some(affix_table) = crafting_index.get_affixes(&item_state.class, affix_side) else {
+            log_debug!("No possible {} found for this item.", affix_side);
+            return false;
         };
 
+        let mut rng = rand::rng();
         for _ in 0..count {
-            if affix_list.len() as u8 >= max_affixes {
-                log_debug!("Cannot add {}. Maximum affixes reached.", affix_type);
+            if (affix_list.len() + unrevealed_affixes) as u8 >= max_affixes {
+                log_debug!("Cannot add {}. Maximum affixes reached.", affix_side);
                 return false;
             }
 
-            let possible_affixes = self.get_possible_affixes(
-                class_tiers,
-                &matched_item.class,
+            let Some((affix_id, affix)) = affix_table.choose_random_affix(
                 item_state.item_level,
-                affix_type,
-            );
-
-            if possible_affixes.is_empty() {
-                log_debug!("No possible {} found for this item.", affix_type);
+                &current_affix_ids,
+                &mut rng,
+            ) else {
+                log_debug!("No possible {} found for this item.", affix_side);
                 return false;
-            }
+            };
 
-            if let Some(affix) =
-                self.choose_random_affix(modifiers.clone(), &current_affixes, &possible_affixes)
-            {
-                log_debug!("Added {}: {}", affix_type, affix.name);
-                affix_list.push(affix);
-            }
+            log_debug!("Added {}: {}", affix_side, affix.name);
+            current_affix_ids.push(affix_id);
+            affix_list.push(affix);
         }
 
         true
@@ -183,114 +66,287 @@ pub trait Crafter {
     fn remove_random_affix(
         &self,
         item_state: &mut ItemState,
-        affix_type: &str,
+        affix_side: AffixSide,
         count: i32,
     ) -> bool {
-        let affix_list = match affix_type {
-            "prefix" => &mut item_state.prefixes,
-            "suffix" => &mut item_state.suffixes,
-            _ => {
-                log_debug!("Error: Invalid affix type");
-                return false;
-            }
+        let affix_list = match affix_side {
+            AffixSide::Prefix => &mut item_state.prefixes,
+            AffixSide::Suffix => &mut item_state.suffixes,
         };
 
         for _ in 0..count {
-            if affix_list.is_empty() {
-                log_debug!("No {} to remove.", affix_type);
+            // fractured modifiers can never be removed
+            let removable_indices = (0..affix_list.len())
+                .filter(|i| !affix_list[*i].fractured)
+                .collect::<Vec<usize>>();
+            let mut rng = rand::rng();
+            let Some(removed_index) = removable_indices.choose(&mut rng) else {
+                log_debug!("No {} to remove.", affix_side);
                 return false;
+            };
+
+            let removed_affix = affix_list.swap_remove(*removed_index);
+            log_debug!("Removed {}: {}", affix_side, removed_affix.name);
+        }
+
+        true
+    }
+
+    /// Adds an unrevealed desecrated modifier placeholder to `item_state`.
+    fn add_unrevealed_affix(&self, item_state: &mut ItemState, affix_side: AffixSide) -> bool {
+        if item_state.has_unrevealed_affix() || item_state.has_desecrated_affixes() {
+            log_debug!("Cannot desecrate, item already has a desecrated modifier.");
+            return false;
+        }
+
+        let has_max_affixes = match affix_side {
+            AffixSide::Prefix => item_state.has_max_prefixes(),
+            AffixSide::Suffix => item_state.has_max_suffixes(),
+        };
+
+        if has_max_affixes {
+            log_debug!("Cannot desecrate {}. Maximum affixes reached.", affix_side);
+            return false;
+        }
+
+        log_debug!("Added unrevealed desecrated {}.", affix_side);
+        item_state.unrevealed_affix = Some(affix_side);
+        item_state.set_next_action(Some("reveal".to_owned()));
+
+        true
+    }
+
+    /// Reveals the unrevealed desecrated modifier on `item_state`, rolling
+    /// `option_count` weighted abyssal options and keeping the best one for
+    /// `target_state`.
+    fn reveal_unrevealed_affix(
+        &self,
+        crafting_index: &CraftingIndex,
+        id_registry: &IdRegistry,
+        item_state: &mut ItemState,
+        option_count: u8,
+        target_state: &ItemState,
+    ) -> bool {
+        let Some(affix_side) = item_state.unrevealed_affix else {
+            log_debug!("No unrevealed modifier to reveal.");
+            return false;
+        };
+
+        let Some(affix_table) = crafting_index.get_abyssal_affixes(&item_state.class, affix_side)
+        else {
+            log_debug!("No possible abyssal {} found for this item.", affix_side);
+            return false;
+        };
+
+        // an omen targeting desecrated modifiers rolls a second set of options
+        let option_rolls = if item_state.has_targeted_desecrated() {
+            2
+        } else {
+            1
+        };
+
+        let mut rng = rand::rng();
+        let mut options: Vec<Modifier> = Vec::new();
+        for _ in 0..option_rolls {
+            let mut rolled_ids = id_registry.get_affix_ids(item_state);
+            for _ in 0..option_count {
+                let Some((option_id, option)) =
+                    affix_table.choose_random_affix(item_state.item_level, &rolled_ids, &mut rng)
+                else {
+                    break;
+                };
+                rolled_ids.push(option_id);
+                options.push(option);
             }
+        }
 
-            let mut rng = rand::rng();
-            let removed_affix = affix_list.swap_remove(rng.random_range(0..affix_list.len()));
-            log_debug!("Removed {}: {}", affix_type, removed_affix.name);
+        if options.is_empty() {
+            log_debug!("No possible abyssal {} found for this item.", affix_side);
+            return false;
+        }
+
+        let target_affixes = match affix_side {
+            AffixSide::Prefix => &target_state.prefixes,
+            AffixSide::Suffix => &target_state.suffixes,
+        };
+        let chosen_index = options
+            .iter()
+            .position(|o| {
+                target_affixes
+                    .iter()
+                    .any(|ta| item_state.meets_modifier(o, ta))
+            })
+            .unwrap_or_default();
+
+        let mut revealed_affix = options.swap_remove(chosen_index);
+        revealed_affix.desecrated = true;
+        log_debug!(
+            "Revealed desecrated {}: {}",
+            affix_side,
+            revealed_affix.name
+        );
+
+        match affix_side {
+            AffixSide::Prefix => item_state.prefixes.push(revealed_affix),
+            AffixSide::Suffix => item_state.suffixes.push(revealed_affix),
         }
+        item_state.unrevealed_affix = None;
+        item_state.clear_desecrated_target();
 
         true
     }
 
-    /// Gets an affix ("prefix" or "suffix") from `outcome`.
-    fn get_outcome_affix(&self, outcome: &CraftOutcome, item_state: &ItemState) -> &str {
-        let is_random_add = outcome.affix == "random" && outcome.action == "add";
-        let is_random_remove = outcome.affix == "random" && outcome.action == "remove";
+    /// Removes a random desecrated modifier from `item_state`.
+    fn remove_desecrated_affix(&self, item_state: &mut ItemState) -> bool {
+        let desecrated_affixes = item_state
+            .prefixes
+            .iter()
+            .chain(item_state.suffixes.iter())
+            .filter(|m| m.desecrated)
+            .map(|m| m.id.clone())
+            .collect::<Vec<String>>();
+        item_state.clear_desecrated_target();
+
+        let mut rng = rand::rng();
+        let Some(removed_id) = desecrated_affixes.choose(&mut rng) else {
+            log_debug!("No desecrated modifier to remove.");
+            return false;
+        };
+
+        item_state.prefixes.retain(|m| !m.id.eq(removed_id));
+        item_state.suffixes.retain(|m| !m.id.eq(removed_id));
+        log_debug!("Removed desecrated modifier: {}", removed_id);
+
+        true
+    }
+
+    /// Gets the affix side from `outcome`, or `None` if there is none it
+    /// can apply to.
+    fn get_outcome_affix(
+        &self,
+        outcome: &CraftOutcome,
+        item_state: &ItemState,
+    ) -> Option<AffixSide> {
+        let is_random = outcome.affix == OutcomeAffix::Random;
+        let is_random_add = is_random
+            && (outcome.action == OutcomeAction::Add || outcome.action == OutcomeAction::Desecrate);
+        // replacing removes an affix first, so it needs one that's removable
+        let is_random_remove = is_random
+            && (outcome.action == OutcomeAction::Remove
+                || outcome.action == OutcomeAction::Replace);
 
         if (item_state.has_max_affixes() && is_random_add)
-            || (item_state.has_no_affixes() && is_random_remove)
+            || (item_state.has_no_removable_affixes() && is_random_remove)
         {
-            ""
+            None
         } else if (item_state.has_max_prefixes() && is_random_add)
-            || (item_state.has_no_prefixes() && is_random_remove)
+            || (item_state.has_no_removable_prefixes() && is_random_remove)
             || (item_state.has_targeted_suffixes()
                 && !item_state.has_max_suffixes()
                 && is_random_add)
             || (item_state.has_targeted_suffixes()
-                && !item_state.has_no_suffixes()
+                && !item_state.has_no_removable_suffixes()
                 && is_random_remove)
             || (item_state.prefixes.len() == 1 && item_state.has_no_suffixes() && is_random_add)
         {
-            "suffix"
+            Some(AffixSide::Suffix)
         } else if (item_state.has_max_suffixes() && is_random_add)
-            || (item_state.has_no_suffixes() && is_random_remove)
+            || (item_state.has_no_removable_suffixes() && is_random_remove)
             || (item_state.has_targeted_prefixes()
                 && !item_state.has_max_prefixes()
                 && is_random_add)
             || (item_state.has_targeted_prefixes()
-                && !item_state.has_no_prefixes()
+                && !item_state.has_no_removable_prefixes()
                 && is_random_remove)
             || (item_state.suffixes.len() == 1 && item_state.has_no_prefixes() && is_random_add)
         {
-            "prefix"
+            Some(AffixSide::Prefix)
         } else {
             let mut rng = rand::rng();
-            if (outcome.affix == "random" && rng.random_bool(0.5)) || outcome.affix == "prefix" {
-                "prefix"
+            if (is_random && rng.random_bool(0.5)) || outcome.affix == OutcomeAffix::Prefix {
+                Some(AffixSide::Prefix)
             } else {
-                "suffix"
+                Some(AffixSide::Suffix)
             }
         }
     }
 
-    /// Gets a minimum `affix` value from `class_tiers` by `tier`.
-    fn get_minimum_affix_value(&self, class_tiers: &[ClassTier], affix: String, tier: u8) -> u16 {
-        class_tiers
-            .iter()
-            .flat_map(|ct| [ct.prefixes.as_slice(), ct.suffixes.as_slice()].concat())
-            .find(|a| a.affix.eq(&affix))
-            .map(|a| a.get_minimum_tier_value(tier).unwrap_or_default())
+    /// Gets a minimum `affix` value from the tiers of a `class_tier` by
+    /// `tier`.
+    fn get_minimum_affix_value(&self, class_tier: &ClassTier, affix: &str, tier: u8) -> u16 {
+        class_tier
+            .get_affix(affix)
+            .and_then(|a| a.get_minimum_tier_value(tier))
             .unwrap_or_default()
     }
 
-    /// Gets the `affix` tier from `class_tiers` by `value`.
-    fn get_affix_tier(&self, class_tiers: &[ClassTier], affix: String, value: u16) -> u8 {
-        class_tiers
-            .iter()
-            .flat_map(|ct| [ct.prefixes.as_slice(), ct.suffixes.as_slice()].concat())
-            .find(|a| a.affix.eq(&affix))
-            .map(|a| a.get_value_tier(value).unwrap_or_default())
+    /// Gets the `affix` tier from the tiers of a `class_tier` by `value`.
+    fn get_affix_tier(&self, class_tier: &ClassTier, affix: &str, value: u16) -> u8 {
+        class_tier
+            .get_affix(affix)
+            .and_then(|a| a.get_value_tier(value))
             .unwrap_or_default()
     }
 
-    /// Gets the weight for an `affix` `tier`.
-    fn get_affix_tier_weight(&self, class_tiers: &[ClassTier], affix: String, tier: u8) -> u16 {
-        class_tiers
-            .iter()
-            .flat_map(|ct| [ct.prefixes.as_slice(), ct.suffixes.as_slice()].concat())
-            .find(|a| a.affix.eq(&affix))
-            .map(|a| a.get_tier_weight(tier).unwrap_or_default())
+    /// Gets the weight for an `affix` `tier` from the tiers of a `class_tier`.
+    fn get_affix_tier_weight(&self, class_tier: &ClassTier, affix: &str, tier: u8) -> u16 {
+        class_tier
+            .get_affix(affix)
+            .and_then(|a| a.get_tier_weight(tier))
             .unwrap_or_default()
     }
 
-    /// Determines if the `action` is a valid action given the current `item`.
-    fn is_valid_crafting_action(&self, action: &CraftAction, item: &ItemState) -> bool {
+    /// Upgrades the rarity of `item_state` if `action` upgrades it, along
+    /// with its affix slots.
+    fn upgrade_item_rarity(&self, items: &Items, action: &CraftAction, item_state: &mut ItemState) {
+        if let Some(rarity) = action.upgrades_to
+            && rarity != item_state.rarity
+        {
+            item_state.set_affix_slots(&items.get_affix_slots(&item_state.base, rarity));
+            log_debug!("updated item rarity to {}", item_state.rarity);
+        }
+    }
+
+    /// Determines if the `action` is a valid action given the current `item`,
+    /// using the affix slots of `items`.
+    fn is_valid_crafting_action(
+        &self,
+        items: &Items,
+        action: &CraftAction,
+        item: &ItemState,
+    ) -> bool {
+        let meets_item_rarity = action.rarity.is_none_or(|rarity| rarity == item.rarity);
+
+        // actions that upgrade rarity add affixes into the slots of the new rarity
+        let upgraded_item;
+        let item = if action.upgrades_to.is_some() {
+            let mut item = item.clone();
+            self.upgrade_item_rarity(items, action, &mut item);
+            upgraded_item = item;
+            &upgraded_item
+        } else {
+            item
+        };
+
         let can_add_prefix = action.only_adds_prefix() && !item.has_max_prefixes();
         let can_add_suffix = action.only_adds_suffix() && !item.has_max_suffixes();
-        let can_remove_prefix = action.only_removes_prefix() && !item.has_no_prefixes();
-        let can_remove_suffix = action.only_removes_suffix() && !item.has_no_suffixes();
+        let can_remove_prefix = action.only_removes_prefix() && !item.has_no_removable_prefixes();
+        let can_remove_suffix = action.only_removes_suffix() && !item.has_no_removable_suffixes();
         let can_add_affix = action.adds_affix() && !item.has_max_affixes();
-        let can_remove_affix = action.removes_affix() && !item.has_no_affixes();
-        let can_replace_affix = action.replaces_affix() && !item.has_no_affixes();
-        let can_target_affix =
-            (action.targets_affix() || action.targets_lowest_tier()) && !item.has_no_affixes();
+        let can_remove_affix = action.removes_affix() && !item.has_no_removable_affixes();
+        let can_replace_affix = action.replaces_affix() && !item.has_no_removable_affixes();
+        let can_target_affix = ((action.targets_affix() && !action.targets_desecrated())
+            || action.targets_lowest_tier())
+            && !item.has_no_affixes();
+        let can_target_desecrated = action.targets_desecrated()
+            && !item.has_targeted_desecrated()
+            && ((action.expects_reveal() && item.has_unrevealed_affix())
+                || (!action.expects_reveal() && item.has_desecrated_affixes()));
+        let can_desecrate = action.desecrates_affix()
+            && !item.has_max_affixes()
+            && !item.has_unrevealed_affix()
+            && !item.has_desecrated_affixes();
+        let can_reveal = action.reveals_affix() && item.has_unrevealed_affix();
         let can_target_prefix = action.only_targets_prefix()
             && !item.has_no_prefixes()
             && !item.has_targeted_prefixes();
@@ -302,11 +358,6 @@ pub trait Crafter {
                 || (item.has_next_action() && item.get_next_actions().contains(&action.id)));
         let can_apply_without_impacting_rarity = !action.id.eq("alteration")
             || (item.get_affix_count() < 2 && !action.id.eq("alteration"));
-        let meets_item_rarity = if action.rarity.is_some() {
-            action.rarity.clone().unwrap().eq(&item.rarity)
-        } else {
-            true
-        };
 
         meets_item_rarity
             && can_apply_without_impacting_rarity
@@ -319,6 +370,9 @@ pub trait Crafter {
                 || can_remove_affix
                 || can_replace_affix
                 || can_target_affix
+                || can_target_desecrated
+                || can_desecrate
+                || can_reveal
                 || can_target_prefix
                 || can_target_suffix)
     }
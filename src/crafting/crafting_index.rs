@@ -0,0 +1,116 @@
+use crate::{
+    crafting::{affix_table::AffixTable, compiled_affix::CompiledAffix},
+    datasets::{affix_tier::AffixTier, class_tier::ClassTier, modifiers::Modifiers},
+    items::{affix_side::AffixSide, id_registry::IdRegistry},
+};
+use logger::log_debug;
+use std::collections::HashMap;
+
+/// The affix tables of every item class and side, built once from the class
+/// tiers so that rolling affixes doesn't need to search the datasets.
+#[derive(Clone, Default)]
+pub struct CraftingIndex {
+    /// Prefix and suffix tables by class.
+    affixes: HashMap<String, [AffixTable; 2]>,
+    /// Abyssal prefix and suffix tables by class.
+    abyssal_affixes: HashMap<String, [AffixTable; 2]>,
+}
+
+impl CraftingIndex {
+    /// Makes a new [`CraftingIndex`] from `class_tiers`, naming affixes
+    /// from `modifiers` and identifying them through `id_registry`.
+    pub fn new(class_tiers: &[ClassTier], modifiers: &Modifiers, id_registry: &IdRegistry) -> Self {
+        let mut index = Self::default();
+        for class_tier in class_tiers {
+            for class in &class_tier.classes {
+                // the first class tiers listing a class are the ones used
+                index.affixes.entry(class.clone()).or_insert_with(|| {
+                    [
+                        Self::compile(
+                            class_tier.get_affixes(AffixSide::Prefix),
+                            modifiers,
+                            id_registry,
+                        ),
+                        Self::compile(
+                            class_tier.get_affixes(AffixSide::Suffix),
+                            modifiers,
+                            id_registry,
+                        ),
+                    ]
+                });
+                index
+                    .abyssal_affixes
+                    .entry(class.clone())
+                    .or_insert_with(|| {
+                        [
+                            Self::compile(
+                                class_tier.get_abyssal_affixes(AffixSide::Prefix),
+                                modifiers,
+                                id_registry,
+                            ),
+                            Self::compile(
+                                class_tier.get_abyssal_affixes(AffixSide::Suffix),
+                                modifiers,
+                                id_registry,
+                            ),
+                        ]
+                    });
+            }
+        }
+
+        index
+    }
+
+    /// Compiles `affix_tiers` into an [`AffixTable`].
+    fn compile(
+        affix_tiers: &[AffixTier],
+        modifiers: &Modifiers,
+        id_registry: &IdRegistry,
+    ) -> AffixTable {
+        AffixTable::new(
+            affix_tiers
+                .iter()
+                .filter_map(|affix_tier| {
+                    let Some(affix) = modifiers
+                        .modifiers
+                        .iter()
+                        .find(|m| m.id.eq(&affix_tier.affix))
+                    else {
+                        log_debug!("No modifier found for affix: {}", affix_tier.affix);
+                        return None;
+                    };
+                    let affix_id = id_registry.affixes.get_id(&affix_tier.affix)?;
+                    CompiledAffix::new(affix_tier, affix, affix_id)
+                })
+                .collect(),
+        )
+    }
+
+    /// Gets the index of the `affix_side` table.
+    fn get_side(affix_side: AffixSide) -> usize {
+        match affix_side {
+            AffixSide::Prefix => 0,
+            AffixSide::Suffix => 1,
+        }
+    }
+
+    /// Gets the affixes (prefixes or suffixes) that can roll on an item
+    /// of `item_class`.
+    pub fn get_affixes(&self, item_class: &str, affix_side: AffixSide) -> Option<&AffixTable> {
+        self.affixes
+            .get(item_class)
+            .map(|tables| &tables[Self::get_side(affix_side)])
+    }
+
+    /// Gets the abyssal affixes (prefixes or suffixes) that can be revealed
+    /// on an item of `item_class`.
+    pub fn get_abyssal_affixes(
+        &self,
+        item_class: &str,
+        affix_side: AffixSide,
+    ) -> Option<&AffixTable> {
+        self.abyssal_affixes
+            .get(item_class)
+            .map(|tables| &tables[Self::get_side(affix_side)])
+    }
+}
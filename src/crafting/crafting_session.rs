@@ -0,0 +1,74 @@
+use crate::{
+    crafting::{action_recommendation::ActionRecommendation, solver::Solver},
+    items::item_state::ItemState,
+};
+
+/// A hands-on crafting session on a single item towards a target, which
+/// keeps the history of the item to undo and redo crafting actions.
+pub struct CraftingSession<'a> {
+    solver: &'a Solver,
+    pub target: ItemState,
+    history: Vec<ItemState>,
+    position: usize,
+}
+
+impl<'a> CraftingSession<'a> {
+    /// Makes a new [`CraftingSession`], crafting `item` towards `target`.
+    pub fn new(solver: &'a Solver, item: ItemState, target: ItemState) -> Self {
+        Self {
+            solver,
+            target,
+            history: vec![item],
+            position: 0,
+        }
+    }
+
+    /// Gets the item in its current state.
+    pub fn get_item(&self) -> &ItemState {
+        &self.history[self.position]
+    }
+
+    /// Applies a crafting action by `action_id` to the item, discarding any
+    /// undone states, and returns if it could be used.
+    pub fn apply(&mut self, action_id: &str) -> bool {
+        let mut item = self.get_item().clone();
+        if !self.solver.apply_action(&mut item, action_id, &self.target) {
+            return false;
+        }
+
+        self.history.truncate(self.position + 1);
+        self.history.push(item);
+        self.position += 1;
+        true
+    }
+
+    /// Undoes the last crafting action, and returns if there was one.
+    pub fn undo(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        self.position -= 1;
+        true
+    }
+
+    /// Redoes the last undone crafting action, and returns if there was one.
+    pub fn redo(&mut self) -> bool {
+        if self.position + 1 >= self.history.len() {
+            return false;
+        }
+        self.position += 1;
+        true
+    }
+
+    /// Gets the ids of every crafting action that can be used on the item.
+    pub fn get_valid_actions(&self) -> Vec<String> {
+        self.solver.get_valid_actions(self.get_item())
+    }
+
+    /// Gets the odds of attaining the target after each candidate next
+    /// action, estimated over `runs`.
+    pub fn get_odds(&self, runs: u32, steps_per_run: u32) -> Vec<ActionRecommendation> {
+        self.solver
+            .recommend_next_actions(self.get_item(), &self.target, runs, steps_per_run)
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{files::versioned::Versioned, items::item_state::ItemState};
+use serde_derive::{Deserialize, Serialize};
+
+/// A possible outcome of recombining two items, where a missing `item`
+/// means recombining failed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecombineOutcome {
+    pub item: Option<ItemState>,
+    pub probability: f32,
+}
+
+impl RecombineOutcome {
+    /// Determines if the outcome is a failed recombination.
+    pub fn is_failure(&self) -> bool {
+        self.item.is_none()
+    }
+
+    /// Gets a user-friendly description of the outcome, e.g.:
+    /// "42.00% chance to keep max-life and chaos-res".
+    pub fn describe(&self) -> String {
+        let percent = self.probability * 100.0;
+        match &self.item {
+            None => format!("{percent:.2}% chance to fail"),
+            Some(item) if item.has_no_affixes() => format!("{percent:.2}% chance to keep nothing"),
+            Some(item) => {
+                let ids = item
+                    .prefixes
+                    .iter()
+                    .chain(item.suffixes.iter())
+                    .map(|m| m.id.as_str())
+                    .collect::<Vec<&str>>();
+                let kept = match ids.split_last() {
+                    Some((last, [])) => last.to_string(),
+                    Some((last, rest)) => format!("{} and {last}", rest.join(", ")),
+                    None => String::new(),
+                };
+                format!("{percent:.2}% chance to keep {kept}")
+            }
+        }
+    }
+}
+
+impl Versioned for RecombineOutcome {}
@@ -0,0 +1,40 @@
+use crate::{
+    files::versioned::Versioned,
+    items::{item_state::ItemState, modifier::Modifier},
+};
+use logger::log_info;
+use serde_derive::{Deserialize, Serialize};
+
+/// A planned recombination of two owned items, and the modifiers to carry
+/// from each of them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecombinePlan {
+    pub left_item: ItemState,
+    pub right_item: ItemState,
+    pub left_mods: Vec<Modifier>,
+    pub right_mods: Vec<Modifier>,
+    pub target_chance: f32,
+}
+
+impl RecombinePlan {
+    /// Prints a user-friendly representation of a recombine plan.
+    pub fn display(&self) {
+        let mod_ids = |mods: &[Modifier]| {
+            mods.iter()
+                .map(|m| m.id.as_str())
+                .collect::<Vec<&str>>()
+                .join(", ")
+        };
+        log_info!(
+            "recombine plan ({:.2}% chance to meet the target):",
+            self.target_chance * 100.0
+        );
+        log_info!("  - keep [{}] from the left item", mod_ids(&self.left_mods));
+        log_info!(
+            "  - keep [{}] from the right item",
+            mod_ids(&self.right_mods)
+        );
+    }
+}
+
+impl Versioned for RecombinePlan {}
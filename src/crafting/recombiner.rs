@@ -1,83 +1,452 @@
-use rand::Rng;
-
 use crate::{
-    datasets::class_tier::ClassTier,
-    items::{item_state::ItemState, modifier::Modifier},
-    random::randomizer,
+    crafting::{recombine_outcome::RecombineOutcome, recombine_plan::RecombinePlan},
+    datasets::{
+        affix_slots::AffixSlots, class_tier::ClassTier, items::Items, recombination::Recombination,
+        recombine_base::RecombineBase, recombine_item_level::RecombineItemLevel,
+    },
+    items::{item_state::ItemState, modifier::Modifier, rarity::Rarity},
+};
+use rand::{
+    distr::{Distribution, weighted::WeightedIndex},
+    seq::IndexedRandom,
 };
 
 /// Responsible for all item combination actions.
 pub trait Recombiner {
-    /// Gets the amount of recombine modifiers based on `modifier_count`.
-    fn get_amount_of_modifers(&self, modifier_count: u8) -> u8 {
-        match modifier_count {
-            1 => randomizer::if_more_than(41.0, 1, 0),
-            2 => randomizer::if_more_than(66.7, 2, 1),
-            3 => randomizer::if_more_than(90.0, 3, randomizer::if_more_than(50.0, 2, 1)),
-            4 => randomizer::if_more_than(70.0, 3, randomizer::if_more_than(60.0, 2, 1)),
-            5 => randomizer::if_more_than(43.0, 3, 2),
-            6 => randomizer::if_more_than(30.0, 3, 2),
-            _ => 0,
+    /// Gets the amount of recombine modifiers based on `modifier_count`,
+    /// using the probability tables from `recombination`.
+    fn get_amount_of_modifers(&self, recombination: &Recombination, modifier_count: u8) -> u8 {
+        let modifier_counts = recombination.get_modifier_counts(modifier_count);
+        if modifier_counts.is_empty() {
+            return 0;
         }
+
+        let dist = WeightedIndex::new(modifier_counts.iter().map(|mc| mc.probability))
+            .expect("Could not distribute modifier count probabilities.");
+        modifier_counts[dist.sample(&mut rand::rng())].kept
     }
 
+    /// Gets the prefixes and suffixes of `left_item` and `right_item` that
+    /// were chosen to be recombined by `left_mods` and `right_mods`.
+    fn get_selected_modifiers(
+        &self,
+        left_item: &ItemState,
+        right_item: &ItemState,
+        left_mods: &[Modifier],
+        right_mods: &[Modifier],
+    ) -> (Vec<Modifier>, Vec<Modifier>) {
+        let is_selected = |modifier: &Modifier, selected_mods: &[Modifier]| {
+            selected_mods.iter().any(|m| m.id.eq(&modifier.id))
+        };
+
+        let prefixes = left_item
+            .prefixes
+            .iter()
+            .filter(|p| is_selected(p, left_mods))
+            .chain(
+                right_item
+                    .prefixes
+                    .iter()
+                    .filter(|p| is_selected(p, right_mods)),
+            )
+            .cloned()
+            .collect::<Vec<Modifier>>();
+        let suffixes = left_item
+            .suffixes
+            .iter()
+            .filter(|s| is_selected(s, left_mods))
+            .chain(
+                right_item
+                    .suffixes
+                    .iter()
+                    .filter(|s| is_selected(s, right_mods)),
+            )
+            .cloned()
+            .collect::<Vec<Modifier>>();
+
+        (prefixes, suffixes)
+    }
+
+    /// Picks `modifier_count` modifiers from `prefixes` and `suffixes`, with
+    /// every combination that fits in `slots` being equally likely.
     fn pick_from_selected_modifiers(
         &self,
+        slots: &AffixSlots,
         modifier_count: u8,
         prefixes: &[Modifier],
         suffixes: &[Modifier],
-    ) -> Vec<Modifier> {
-        let affix = if rand::rng().random_bool(0.5) {
-            "prefix"
+    ) -> (Vec<Modifier>, Vec<Modifier>) {
+        self.get_modifier_combinations(slots, modifier_count, prefixes, suffixes)
+            .choose(&mut rand::rng())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Gets every combination of `modifier_count` modifiers from `prefixes`
+    /// and `suffixes` that fits in `slots`, as pairs of prefixes and suffixes.
+    fn get_modifier_combinations(
+        &self,
+        slots: &AffixSlots,
+        modifier_count: u8,
+        prefixes: &[Modifier],
+        suffixes: &[Modifier],
+    ) -> Vec<(Vec<Modifier>, Vec<Modifier>)> {
+        let max_prefixes = prefixes.len().min(slots.prefixes.into());
+        let max_suffixes = suffixes.len().min(slots.suffixes.into());
+        let modifier_count = (modifier_count as usize).min(max_prefixes + max_suffixes);
+
+        (0..=modifier_count.min(max_prefixes))
+            .filter(|prefix_count| modifier_count - prefix_count <= max_suffixes)
+            .flat_map(|prefix_count| {
+                let prefix_combinations = self.get_combinations(prefixes, prefix_count);
+                let suffix_combinations =
+                    self.get_combinations(suffixes, modifier_count - prefix_count);
+                prefix_combinations
+                    .iter()
+                    .flat_map(|p| {
+                        suffix_combinations
+                            .iter()
+                            .map(|s| (p.clone(), s.clone()))
+                            .collect::<Vec<(Vec<Modifier>, Vec<Modifier>)>>()
+                    })
+                    .collect::<Vec<(Vec<Modifier>, Vec<Modifier>)>>()
+            })
+            .collect()
+    }
+
+    /// Gets every combination of `count` modifiers from `modifiers`.
+    fn get_combinations(&self, modifiers: &[Modifier], count: usize) -> Vec<Vec<Modifier>> {
+        if count == 0 {
+            return vec![vec![]];
+        }
+        if modifiers.len() < count {
+            return vec![];
+        }
+
+        // every combination either starts with the first modifier, or skips it
+        let mut combinations = self
+            .get_combinations(&modifiers[1..], count - 1)
+            .into_iter()
+            .map(|c| [vec![modifiers[0].clone()], c].concat())
+            .collect::<Vec<Vec<Modifier>>>();
+        combinations.extend(self.get_combinations(&modifiers[1..], count));
+        combinations
+    }
+
+    /// Enumerates every possible outcome of recombining `left_item` and
+    /// `right_item` with the chosen `left_mods` and `right_mods`, along with
+    /// its exact probability. Equivalent outcomes are grouped together, and
+    /// outcomes are sorted from most to least likely.
+    #[allow(clippy::too_many_arguments)]
+    fn enumerate_recombine_outcomes(
+        &self,
+        item_bases: &Items,
+        recombinations: &[Recombination],
+        class_tiers: &[ClassTier],
+        left_item: &ItemState,
+        right_item: &ItemState,
+        left_mods: &[Modifier],
+        right_mods: &[Modifier],
+    ) -> Vec<RecombineOutcome> {
+        let Some(recombination) =
+            item_bases.find_for_class(&left_item.class, recombinations, |r| &r.classes)
+        else {
+            return vec![];
+        };
+        let base_item =
+            self.select_recombine_item(recombination, left_item, right_item, left_mods, right_mods);
+        let item_level =
+            self.get_recombined_item_level(recombination, &base_item, left_item, right_item);
+        let Some(class_tier) = class_tiers
+            .iter()
+            .find(|ct| ct.classes.contains(&base_item.class))
+        else {
+            return vec![];
+        };
+
+        let (prefixes, suffixes) =
+            self.get_selected_modifiers(left_item, right_item, left_mods, right_mods);
+        let modifier_count = (prefixes.len() + suffixes.len()) as u8;
+
+        // an unknown amount of modifiers always keeps none of them
+        let modifier_counts = recombination.get_modifier_counts(modifier_count);
+        let kept_counts = if modifier_counts.is_empty() {
+            vec![(0, 1.0)]
         } else {
-            "suffix"
+            let total_probability = modifier_counts.iter().map(|mc| mc.probability).sum::<f32>();
+            modifier_counts
+                .iter()
+                .map(|mc| (mc.kept, mc.probability / total_probability))
+                .collect::<Vec<(u8, f32)>>()
         };
 
-        let mut prefixes_picked: i16 = -1;
-        let mut suffixes_picked: i16 = -1;
-        (0..modifier_count)
-            .map(|_| {
-                if (affix.eq("prefix") && prefixes_picked < (prefixes.len() - 1) as i16)
-                    || (affix.eq("suffix") && suffixes_picked >= (suffixes.len() - 1) as i16)
-                {
-                    prefixes_picked += 1;
-                    prefixes[prefixes_picked as usize].clone()
-                } else {
-                    suffixes_picked += 1;
-                    suffixes[suffixes_picked as usize].clone()
-                }
+        let mut outcomes: Vec<RecombineOutcome> = Vec::new();
+        let mut add_outcome = |item: Option<ItemState>, probability: f32| {
+            if probability <= 0.0 {
+                return;
+            }
+            if let Some(outcome) = outcomes.iter_mut().find(|o| o.item == item) {
+                outcome.probability += probability;
+            } else {
+                outcomes.push(RecombineOutcome { item, probability });
+            }
+        };
+
+        // recombined items can fill every slot of a rare base
+        let slots = item_bases.get_affix_slots(&base_item.base, Rarity::Rare);
+        for (kept, kept_probability) in kept_counts {
+            let combinations = self.get_modifier_combinations(&slots, kept, &prefixes, &suffixes);
+            let combination_probability = kept_probability / combinations.len().max(1) as f32;
+
+            for (mut kept_prefixes, mut kept_suffixes) in combinations {
+                let modifiers = [kept_prefixes.as_slice(), kept_suffixes.as_slice()].concat();
+                let success_chance = self.get_recombine_success_chance(
+                    class_tier,
+                    recombination,
+                    item_level,
+                    &modifiers,
+                ) / 100.0;
+
+                // sort modifiers so equivalent items are grouped together
+                kept_prefixes.sort_by(|a, b| a.id.cmp(&b.id));
+                kept_suffixes.sort_by(|a, b| a.id.cmp(&b.id));
+                let recombined_slots = self.get_recombined_slots(
+                    item_bases,
+                    &base_item.base,
+                    &kept_prefixes,
+                    &kept_suffixes,
+                );
+                let recombined_item = ItemState::new(
+                    &base_item.base,
+                    &base_item.class,
+                    recombined_slots,
+                    item_level,
+                    kept_prefixes,
+                    kept_suffixes,
+                );
+
+                add_outcome(
+                    Some(recombined_item),
+                    combination_probability * success_chance,
+                );
+                add_outcome(None, combination_probability * (1.0 - success_chance));
+            }
+        }
+
+        outcomes.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+        outcomes
+    }
+
+    /// Gets the chance that any of the recombine `outcomes` meets `target_state`.
+    fn get_recombine_target_chance(
+        &self,
+        outcomes: &[RecombineOutcome],
+        target_state: &ItemState,
+    ) -> f32 {
+        outcomes
+            .iter()
+            .filter(|o| {
+                o.item
+                    .as_ref()
+                    .is_some_and(|i| i.meets_target(target_state))
             })
-            .collect::<Vec<Modifier>>()
+            .map(|o| o.probability)
+            .sum()
     }
 
-    /// Selects a base from two items of the same class.
+    /// Plans the recombination of two of the owned `items`, and which of
+    /// their modifiers to carry, that has the highest chance of meeting
+    /// `target_state`.
+    fn plan_recombination(
+        &self,
+        item_bases: &Items,
+        recombinations: &[Recombination],
+        class_tiers: &[ClassTier],
+        target_state: &ItemState,
+        items: &[ItemState],
+    ) -> Option<RecombinePlan> {
+        let target_mods = [
+            target_state.prefixes.as_slice(),
+            target_state.suffixes.as_slice(),
+        ]
+        .concat();
+        let mut best_plan: Option<RecombinePlan> = None;
+
+        for (left_index, left_item) in items.iter().enumerate() {
+            for right_item in items.iter().skip(left_index + 1) {
+                let Some(recombination) =
+                    item_bases.find_for_class(&left_item.class, recombinations, |r| &r.classes)
+                else {
+                    continue;
+                };
+                if !left_item.class.eq(&right_item.class) {
+                    continue;
+                }
+
+                let left_mod_sets = self.get_modifier_subsets(left_item);
+                let right_mod_sets = self.get_modifier_subsets(right_item);
+
+                for left_mods in &left_mod_sets {
+                    for right_mods in &right_mod_sets {
+                        let selected_mods = [left_mods.as_slice(), right_mods.as_slice()].concat();
+                        let modifier_count = selected_mods.len() as u8;
+
+                        // skip selections that can't recombine, or can't ever meet the target
+                        if recombination.get_modifier_counts(modifier_count).is_empty()
+                            || !target_mods.iter().all(|tm| {
+                                selected_mods
+                                    .iter()
+                                    .any(|sm| target_state.meets_modifier(sm, tm))
+                            })
+                        {
+                            continue;
+                        }
+
+                        let outcomes = self.enumerate_recombine_outcomes(
+                            item_bases,
+                            recombinations,
+                            class_tiers,
+                            left_item,
+                            right_item,
+                            left_mods,
+                            right_mods,
+                        );
+                        let target_chance =
+                            self.get_recombine_target_chance(&outcomes, target_state);
+
+                        // prefer higher chances, then carrying fewer modifiers
+                        let is_better_plan = best_plan.as_ref().is_none_or(|bp| {
+                            target_chance > bp.target_chance
+                                || (target_chance == bp.target_chance
+                                    && selected_mods.len()
+                                        < bp.left_mods.len() + bp.right_mods.len())
+                        });
+
+                        if target_chance > 0.0 && is_better_plan {
+                            best_plan = Some(RecombinePlan {
+                                left_item: left_item.clone(),
+                                right_item: right_item.clone(),
+                                left_mods: left_mods.clone(),
+                                right_mods: right_mods.clone(),
+                                target_chance,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        best_plan
+    }
+
+    /// Gets every subset of the modifiers on `item_state`.
+    fn get_modifier_subsets(&self, item_state: &ItemState) -> Vec<Vec<Modifier>> {
+        let modifiers = [
+            item_state.prefixes.as_slice(),
+            item_state.suffixes.as_slice(),
+        ]
+        .concat();
+        (0..=modifiers.len())
+            .flat_map(|count| self.get_combinations(&modifiers, count))
+            .collect()
+    }
+
+    /// Selects a base from two items of the same class, using the
+    /// `recombination` base rule.
     fn select_recombine_item(
         &self,
+        recombination: &Recombination,
         left_item: &ItemState,
         right_item: &ItemState,
         left_mods: &[Modifier],
         right_mods: &[Modifier],
     ) -> ItemState {
-        let mut left_mod_weights = left_mods.iter().map(|m| m.weight).collect::<Vec<u16>>();
-        let mut right_mod_weights = right_mods.iter().map(|m| m.weight).collect::<Vec<u16>>();
-
-        left_mod_weights.sort();
-        right_mod_weights.sort();
-
-        let lowest_left_mod_weight = left_mod_weights.first().unwrap();
-        let lowest_right_mod_weight = right_mod_weights.first().unwrap();
+        let keeps_left_item = match recombination.base {
+            RecombineBase::ItemLevel => left_item.item_level >= right_item.item_level,
+            RecombineBase::Left => true,
+            RecombineBase::Weight => {
+                let lowest_left_mod_weight = left_mods.iter().map(|m| m.weight).min().unwrap_or(0);
+                let lowest_right_mod_weight =
+                    right_mods.iter().map(|m| m.weight).min().unwrap_or(0);
+                lowest_left_mod_weight >= lowest_right_mod_weight
+            }
+        };
 
-        if lowest_left_mod_weight >= lowest_right_mod_weight {
+        if keeps_left_item {
             left_item.clone()
         } else {
             right_item.clone()
         }
     }
 
+    /// Gets the item level of a recombined item, using the `recombination`
+    /// item level rule.
+    fn get_recombined_item_level(
+        &self,
+        recombination: &Recombination,
+        base_item: &ItemState,
+        left_item: &ItemState,
+        right_item: &ItemState,
+    ) -> u8 {
+        match recombination.item_level {
+            RecombineItemLevel::Lowest => left_item.item_level.min(right_item.item_level),
+            RecombineItemLevel::Average => {
+                ((left_item.item_level as u16 + right_item.item_level as u16) / 2) as u8
+            }
+            RecombineItemLevel::Base => base_item.item_level,
+            RecombineItemLevel::Highest => left_item.item_level.max(right_item.item_level),
+        }
+    }
+
+    /// Gets the rarity and affix slots of a recombined `base` from its
+    /// `prefixes` and `suffixes`, the lowest rarity they fit in.
+    fn get_recombined_slots(
+        &self,
+        item_bases: &Items,
+        base: &str,
+        prefixes: &[Modifier],
+        suffixes: &[Modifier],
+    ) -> AffixSlots {
+        [Rarity::Normal, Rarity::Magic]
+            .into_iter()
+            .map(|rarity| item_bases.get_affix_slots(base, rarity))
+            .find(|slots| slots.fits(prefixes.len(), suffixes.len()))
+            .unwrap_or_else(|| item_bases.get_affix_slots(base, Rarity::Rare))
+    }
+
+    /// Calculates the percent chance that recombining into `modifiers` succeeds.
+    fn get_recombine_success_chance(
+        &self,
+        base_type: &ClassTier,
+        recombination: &Recombination,
+        item_level: u8,
+        modifiers: &[Modifier],
+    ) -> f32 {
+        if modifiers.is_empty() {
+            return 100.0;
+        }
+
+        let success_chance = modifiers
+            .iter()
+            .map(|m| {
+                self.get_modifier_recombine_chance(
+                    base_type,
+                    recombination,
+                    item_level,
+                    &m.id,
+                    m.tier,
+                )
+            })
+            .sum::<f32>();
+
+        (success_chance * 100.0).min(100.0)
+    }
+
     /// Calculates a "success chance" of combining items.
     fn get_modifier_recombine_chance(
         &self,
         base_type: &ClassTier,
+        recombination: &Recombination,
         item_level: u8,
         affix_id: &str,
         target_tier: u8,
@@ -90,25 +459,16 @@ pub trait Recombiner {
 
         // find the highest tier for the affix given the item level
         let highest_tier = base_type.get_highest_affix_tier_for_item_level(affix_id, item_level);
-        if target_tier >= highest_tier {
+        if target_tier < highest_tier {
             return 0.0;
         }
 
-        // get the sum of all weights from target tier to highest tier
-        let sum_of_weights: u32 = (target_tier..highest_tier)
+        // get the sum of all weights from highest tier to target tier
+        let sum_of_weights: u32 = (highest_tier..=target_tier)
             .map(|tier| base_type.get_weight_of_tier(affix_id, tier) as u32)
             .sum();
 
-        // chests get a 5x coefficient, spears get an 8x, everything else does not
-        let coefficient = if base_type.classes.contains(&"chest".to_owned()) {
-            5
-        } else if base_type.classes.contains(&"spear".to_owned()) {
-            8
-        } else {
-            1
-        };
-
-        // return the percent chance of the recombination
-        (coefficient * sum_of_weights) as f32 / total_weight as f32
+        // return the chance of the recombination, scaled by the class coefficient
+        (recombination.coefficient * sum_of_weights) as f32 / total_weight as f32
     }
 }
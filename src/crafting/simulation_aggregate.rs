@@ -0,0 +1,47 @@
+use crate::crafting::simulation_run::SimulationRun;
+
+/// The results of the simulated runs taken by a single worker, merged with
+/// the results of every other worker once they all finish.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimulationAggregate {
+    /// The net cost of each run, and if it succeeded.
+    pub run_costs: Vec<(f32, bool)>,
+    pub successes: u32,
+    pub total_salvage: f32,
+    /// The cost and sequence of the cheapest successful run.
+    pub best_run: Option<(f32, Vec<String>)>,
+}
+
+impl SimulationAggregate {
+    /// Adds the results of a simulated `run`.
+    pub fn add_run(&mut self, run: &SimulationRun) {
+        self.run_costs.push((run.get_net_cost(), run.succeeded));
+        self.total_salvage += run.salvage;
+        if run.succeeded {
+            self.successes += 1;
+            if self
+                .best_run
+                .as_ref()
+                .is_none_or(|(cost, _)| run.cost < *cost)
+            {
+                self.best_run = Some((run.cost, run.sequence.clone()));
+            }
+        }
+    }
+
+    /// Merges the results of `other` into these results.
+    pub fn merge(mut self, other: SimulationAggregate) -> Self {
+        self.run_costs.extend(other.run_costs);
+        self.successes += other.successes;
+        self.total_salvage += other.total_salvage;
+        if let Some((cost, sequence)) = other.best_run
+            && self
+                .best_run
+                .as_ref()
+                .is_none_or(|(best_cost, _)| cost < *best_cost)
+        {
+            self.best_run = Some((cost, sequence));
+        }
+        self
+    }
+}
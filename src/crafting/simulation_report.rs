@@ -0,0 +1,84 @@
+use crate::{
+    crafting::{cost_distribution::CostDistribution, starting_item::StartingItem},
+    datasets::prices::Prices,
+    files::versioned::Versioned,
+};
+use logger::log_info;
+use serde_derive::{Deserialize, Serialize};
+
+/// The results of simulating crafts towards a target, including the risk of
+/// its crafting costs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub runs: u32,
+    pub starting_item: StartingItem,
+    pub successes: u32,
+    pub best_sequence: Vec<String>,
+    pub best_cost: Option<f32>,
+    /// The average resale value of the item left over from a failed run.
+    pub expected_salvage: f32,
+    pub cost_distribution: CostDistribution,
+}
+
+impl SimulationReport {
+    /// Gets the chance (from `0.0` to `1.0`) that a single run succeeds.
+    pub fn get_success_chance(&self) -> f32 {
+        self.successes as f32 / self.runs.max(1) as f32
+    }
+
+    /// Gets the chance (from `0.0` to `1.0`) that attaining the target costs
+    /// more than `threshold`.
+    pub fn get_exceed_chance(&self, threshold: f32) -> f32 {
+        self.cost_distribution.get_exceed_chance(threshold)
+    }
+
+    /// Prints a user-friendly representation of a simulation report, with
+    /// costs reported in `currency`.
+    pub fn display(&self, prices: &Prices, currency: &str) {
+        let Some(best_cost) = self.best_cost else {
+            log_info!("could not find a successful sequence within the given parameters.");
+            return;
+        };
+
+        if self.starting_item.price > 0.0 {
+            log_info!(
+                "starting from: {} (bought for {})",
+                self.starting_item.describe(),
+                prices.format_cost(self.starting_item.price, currency)
+            );
+        }
+        log_info!("optimal sequence found: {:?}", self.best_sequence);
+        log_info!("total cost: {}", prices.format_cost(best_cost, currency));
+
+        let costs = &self.cost_distribution;
+        log_info!(
+            "expected net cost: {} (standard deviation: {})",
+            prices.format_cost(costs.get_mean(), currency),
+            prices.format_cost(costs.get_standard_deviation(), currency)
+        );
+        if self.expected_salvage > 0.0 {
+            log_info!(
+                "  after selling each failed item for {} on average",
+                prices.format_cost(self.expected_salvage, currency)
+            );
+        }
+        for percentile in [50.0, 90.0, 99.0] {
+            log_info!(
+                "  p{percentile}: {}",
+                prices.format_cost(costs.get_percentile(percentile), currency)
+            );
+        }
+        log_info!(
+            "  worst case: {}",
+            prices.format_cost(costs.get_worst_case(), currency)
+        );
+        for multiplier in [2.0, 10.0] {
+            log_info!(
+                "  {:.2}% chance to cost more than {multiplier}x the expected cost",
+                self.get_exceed_chance(costs.get_mean() * multiplier) * 100.0
+            );
+        }
+    }
+}
+
+impl Versioned for SimulationReport {}
@@ -0,0 +1,23 @@
+use crate::{files::versioned::Versioned, items::item_state::ItemState};
+use serde_derive::{Deserialize, Serialize};
+
+/// The result of a single simulated crafting run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationRun {
+    pub item: ItemState,
+    pub sequence: Vec<String>,
+    pub cost: f32,
+    /// The resale value of the item left over from a failed run.
+    pub salvage: f32,
+    pub succeeded: bool,
+}
+
+impl SimulationRun {
+    /// Gets the cost of the run after selling any item left over from it,
+    /// which is negative if the item sells for more than the run cost.
+    pub fn get_net_cost(&self) -> f32 {
+        self.cost - self.salvage
+    }
+}
+
+impl Versioned for SimulationRun {}
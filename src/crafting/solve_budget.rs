@@ -0,0 +1,110 @@
+use crate::crafting::{cancellation_token::CancellationToken, solve_limits::SolveLimits};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Instant,
+};
+
+/// Tracks the time and iterations spent on a single solve against its
+/// [`SolveLimits`] and [`CancellationToken`].
+pub struct SolveBudget {
+    limits: SolveLimits,
+    cancellation_token: CancellationToken,
+    started: Instant,
+    /// The iterations spent, shared with any budgets shared from this one.
+    iterations: Arc<AtomicU64>,
+}
+
+impl SolveBudget {
+    /// Makes a new [`SolveBudget`], starting now.
+    pub fn new(limits: &SolveLimits, cancellation_token: &CancellationToken) -> Self {
+        Self {
+            limits: limits.clone(),
+            cancellation_token: cancellation_token.clone(),
+            started: Instant::now(),
+            iterations: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Makes a budget that may spend only `share` (from `0.0` to `1.0`) of
+    /// the time and iterations left in this one, where everything it spends
+    /// counts towards this one, so the rest stays reserved for this one.
+    pub fn share(&self, share: f32) -> SolveBudget {
+        let share = share.clamp(0.0, 1.0);
+        let elapsed = self.started.elapsed();
+        let iterations = self.iterations.load(Ordering::Relaxed);
+        let limits = SolveLimits {
+            time_limit: self
+                .limits
+                .time_limit
+                .map(|tl| elapsed + tl.saturating_sub(elapsed).mul_f32(share)),
+            max_iterations: self.limits.max_iterations.map(|mi| {
+                iterations + (mi.saturating_sub(iterations) as f32 * share).ceil() as u64
+            }),
+        };
+        Self {
+            limits,
+            cancellation_token: self.cancellation_token.clone(),
+            started: self.started,
+            iterations: Arc::clone(&self.iterations),
+        }
+    }
+
+    /// Predicate that determines if solving must stop, because it was
+    /// cancelled or ran out of time or iterations.
+    pub fn is_exhausted(&self) -> bool {
+        self.is_stopped()
+            || self
+                .limits
+                .max_iterations
+                .is_some_and(|mi| self.iterations.load(Ordering::Relaxed) >= mi)
+    }
+
+    /// Counts an iteration, and returns if it may go ahead.
+    pub fn try_iteration(&self) -> bool {
+        if self.is_stopped() {
+            return false;
+        }
+        // iterations that can't go ahead aren't counted, so they never take
+        // from the budget this one was shared from
+        self.iterations
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |iteration| {
+                self.limits
+                    .max_iterations
+                    .is_none_or(|mi| iteration < mi)
+                    .then_some(iteration + 1)
+            })
+            .is_ok()
+    }
+
+    /// Predicate that determines if solving was cancelled or ran out of time.
+    fn is_stopped(&self) -> bool {
+        self.cancellation_token.is_cancelled()
+            || self
+                .limits
+                .time_limit
+                .is_some_and(|tl| self.started.elapsed() >= tl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_budget_reserves_the_rest_of_the_iterations() {
+        let limits = SolveLimits {
+            max_iterations: Some(8),
+            ..Default::default()
+        };
+        let solve_budget = SolveBudget::new(&limits, &CancellationToken::default());
+        let shared_budget = solve_budget.share(0.25);
+
+        assert_eq!((0..8).filter(|_| shared_budget.try_iteration()).count(), 2);
+        assert!(shared_budget.is_exhausted());
+        assert!(!solve_budget.is_exhausted());
+        assert_eq!((0..8).filter(|_| solve_budget.try_iteration()).count(), 6);
+    }
+}
@@ -0,0 +1,11 @@
+use std::time::Duration;
+
+/// Limits on how long solving may take, after which the best result so far is
+/// returned. There are no limits by default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SolveLimits {
+    /// The most wall-clock time that solving may take.
+    pub time_limit: Option<Duration>,
+    /// The most simulated runs that solving may take.
+    pub max_iterations: Option<u64>,
+}
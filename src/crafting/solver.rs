@@ -1,22 +1,42 @@
 use crate::{
-    crafting::{crafter::Crafter, recombiner::Recombiner},
+    crafting::{
+        action_recommendation::ActionRecommendation, best_cost::BestCost,
+        budget_report::BudgetReport, cancellation_token::CancellationToken,
+        cost_distribution::CostDistribution, craft_plan::CraftPlan, craft_step::CraftStep,
+        crafter::Crafter, crafting_index::CraftingIndex, recombine_outcome::RecombineOutcome,
+        recombine_plan::RecombinePlan, recombiner::Recombiner,
+        simulation_aggregate::SimulationAggregate, simulation_report::SimulationReport,
+        simulation_run::SimulationRun, solve_budget::SolveBudget, solve_limits::SolveLimits,
+        solver_observer::SolverObserver, starting_item::StartingItem, worker_pool,
+    },
     datasets::{
         class_tier::ClassTier, class_tiers::ClassTiers, craft_action::CraftAction,
         craft_actions::CraftActions, craft_outcome::CraftOutcome, items::Items,
-        modifiers::Modifiers,
+        modifiers::Modifiers, outcome_action::OutcomeAction, outcome_affix::OutcomeAffix,
+        prices::Prices, recombinations::Recombinations, valuations::Valuations,
     },
     files::from_file::FromFile,
-    items::{item_state::ItemState, modifier::Modifier},
+    items::{
+        compact_item_state::CompactItemState, id_registry::IdRegistry, interned_id::InternedId,
+        item_state::ItemState, item_text::ItemText, item_text_error::ItemTextError,
+        modifier::Modifier, rarity::Rarity,
+    },
     random::randomizer,
 };
-use logger::{log_debug, log_info};
+use logger::{log_debug, log_error, log_info, log_warning};
 use rand::distr::weighted::WeightedIndex;
 use rand::{distr::Distribution, seq::IndexedRandom};
-use std::{
-    sync::{Arc, Mutex},
-    thread,
-    time::Instant,
-};
+use std::{collections::HashMap, path::Path, process::exit, sync::Arc, time::Instant};
+
+/// The multiple of a budget that crafting sessions may spend, to measure the
+/// tail of the cost distribution.
+const BUDGET_TAIL_MULTIPLIER: f32 = 10.0;
+
+/// The maximum amount of crafting strategies evaluated when solving within a budget.
+const MAX_BUDGET_STRATEGIES: usize = 10;
+
+/// The share of a solve budget that choosing the starting item may spend.
+const STARTING_ITEM_BUDGET_SHARE: f32 = 0.25;
 
 /// Represents a collection of all datasets and crafting functionality.
 #[derive(Default)]
@@ -24,169 +44,725 @@ pub struct Solver {
     pub modifiers: Modifiers,
     pub items: Items,
     pub craft_actions: CraftActions,
+    /// Class tiers resolved through the class hierarchy, one per class.
     pub class_tiers: ClassTiers,
+    /// Affix tables compiled from the class tiers and modifiers.
+    pub crafting_index: CraftingIndex,
+    /// Interned ids of the datasets, for compact item states.
+    pub id_registry: IdRegistry,
+    pub recombinations: Recombinations,
+    pub prices: Prices,
+    /// Resale prices that credit the items left over from failed crafts,
+    /// if `assets/valuations.toml` exists.
+    pub valuations: Option<Valuations>,
+    /// Items that can be bought to start crafting from, besides a new base.
+    pub starting_items: Vec<StartingItem>,
+    /// Observers notified of the progress of the solver.
+    pub observers: Vec<Arc<dyn SolverObserver>>,
+    /// Limits on the time and iterations of every solve.
+    pub limits: SolveLimits,
+    /// Cancels every solve early, returning the best result so far.
+    pub cancellation_token: CancellationToken,
+    /// The currency id that costs are reported in, e.g.: "divine".
+    pub report_currency: String,
     // other datasets would go here, e.g., omens, currencies, etc.
 }
 
 impl Solver {
     /// Creates a new [`Solver`].
     pub fn new() -> Self {
+        let prices = Prices::default().populate("assets/prices.toml");
+        let modifiers = Modifiers::default().populate("assets/modifiers.toml");
+        let items = Items::default().populate("assets/items.toml");
+        let class_tiers = ClassTiers::default()
+            .populate("assets/class_tiers.toml")
+            .resolve(&items);
+        let craft_actions = CraftActions::default().populate("assets/craft_actions.toml");
+        let unpriced_currencies = craft_actions.get_unpriced_currencies(&prices);
+        if !unpriced_currencies.is_empty() {
+            log_error!(
+                "Crafting actions consume currencies without a price in `assets/prices.toml`: {}",
+                unpriced_currencies.join(", ")
+            );
+            exit(1)
+        }
+        let id_registry = IdRegistry::new(&modifiers, &craft_actions, &items, &class_tiers);
         Solver {
-            modifiers: Modifiers::default().populate("assets/modifiers.toml"),
-            items: Items::default().populate("assets/items.toml"),
-            craft_actions: CraftActions::default().populate("assets/craft_actions.toml"),
-            class_tiers: ClassTiers::default().populate("assets/class_tiers.toml"),
+            crafting_index: CraftingIndex::new(&class_tiers.class_tiers, &modifiers, &id_registry),
+            id_registry,
+            modifiers,
+            items,
+            craft_actions,
+            class_tiers,
+            recombinations: Recombinations::default()
+                .populate("assets/recombination.toml")
+                .resolve(),
+            valuations: Path::new("assets/valuations.toml")
+                .exists()
+                .then(|| Valuations::default().populate("assets/valuations.toml")),
+            starting_items: vec![],
+            observers: vec![],
+            limits: SolveLimits::default(),
+            cancellation_token: CancellationToken::default(),
+            report_currency: prices.base_currency.clone(),
+            prices,
         }
     }
 
     /// Simulates attaining the `target_state` over an amount of `runs`, and
-    /// reports the results.
-    pub fn simulate(&self, target_state: &ItemState, runs: u32, steps_per_run: u32) {
-        // wrap shared state in Arc and Mutex to allow safe, multi-threaded access
-        let best_cost = Arc::new(Mutex::new(f32::MAX));
-        let best_sequence = Arc::new(Mutex::new(vec![]));
-
+    /// reports the results, including the risk of the crafting costs.
+    pub fn simulate(
+        &self,
+        target_state: &ItemState,
+        runs: u32,
+        steps_per_run: u32,
+    ) -> SimulationReport {
         let simulation_start = Instant::now();
+        let solve_budget = self.start_solve_budget();
+        // the rest of the budget is reserved for the runs themselves
+        let starting_budget = solve_budget.share(STARTING_ITEM_BUDGET_SHARE);
+        let starting_item =
+            self.choose_starting_item_within(target_state, runs, steps_per_run, &starting_budget);
         log_info!("starting simulation ({runs} runs) to find the best crafting sequence.");
+        self.notify(|o| o.on_simulation_started(runs));
 
-        // create a thread scope to spawn a thread for each simulation
-        thread::scope(|s| {
-            for i in 0..runs {
-                // clone the Arcs for each new thread; this is a cheap operation
-                let best_cost_clone = Arc::clone(&best_cost);
-                let best_sequence_clone = Arc::clone(&best_sequence);
-
-                // spawn a thread in main thread scope for each run
-                s.spawn(move || {
-                    let run_start = Instant::now();
-                    log_debug!("starting run {i}.");
-
-                    let mut crafted_item = ItemState::new(
-                        &target_state.base,
-                        &target_state.class,
-                        "normal",
-                        target_state.item_level,
-                        vec![],
-                        vec![],
+        // workers keep their own results, and only share the best cost so far
+        let best_cost = BestCost::default();
+        let aggregate =
+            worker_pool::run_pooled(runs, SimulationAggregate::default, |aggregate, i| {
+                if !solve_budget.try_iteration() {
+                    return;
+                }
+                let run_start = Instant::now();
+                log_debug!("starting run {i}.");
+                self.notify(|o| o.on_run_started(i));
+
+                let run = self.run_simulation(
+                    target_state,
+                    &starting_item,
+                    steps_per_run,
+                    f32::INFINITY,
+                    None,
+                );
+                self.notify(|o| o.on_run_finished(i, &run));
+                if run.succeeded && best_cost.try_improve(run.cost) {
+                    self.notify(|o| o.on_new_best(i, &run));
+                    log_info!(
+                        "found a new best sequence (cost: {:.2}) after {} iterations!",
+                        run.cost,
+                        i + 1
                     );
+                    run.item.display();
+                }
+                aggregate.add_run(&run);
 
-                    let mut current_cost = 0.0;
-                    let mut sequence: Vec<String> = Vec::new();
-
-                    let mut rng = rand::rng();
-
-                    // apply `steps_per_run` amount of crafts for each run
-                    for _ in 0..steps_per_run {
-                        let good_action_ids =
-                            self.get_crafting_actions(target_state, &crafted_item);
-
-                        if good_action_ids.is_empty() {
-                            log_debug!("can't find any good crafting actions!");
-                            break;
-                        }
-
-                        let action_id = good_action_ids.choose(&mut rng).unwrap();
-                        self.apply_crafting_action(&mut crafted_item, action_id);
-                        current_cost +=
-                            self.craft_actions.get_action_by_id(action_id).unwrap().cost;
-                        sequence.push(action_id.clone());
-
-                        if crafted_item.meets_target(target_state) {
-                            // lock the mutex to safely access and modify the shared state.
-                            let mut locked_cost = best_cost_clone.lock().unwrap();
-                            if current_cost < *locked_cost {
-                                let mut locked_sequence = best_sequence_clone.lock().unwrap();
-                                *locked_cost = current_cost;
-                                *locked_sequence = sequence;
-
-                                log_info!(
-                                    "found a new best sequence (cost: {:.2}) after {} iterations!",
-                                    *locked_cost,
-                                    i + 1
-                                );
-                                crafted_item.display();
-                            }
-                            break;
-                        }
-
-                        // clear out any actions from the crafted item's target
-                        self.reset_item_target_action(&mut crafted_item, action_id);
-                    }
+                log_debug!(
+                    "finished run {} ({:.2}s)",
+                    i + 1,
+                    run_start.elapsed().as_secs_f32()
+                );
+            })
+            .into_iter()
+            .fold(SimulationAggregate::default(), SimulationAggregate::merge);
 
-                    log_debug!(
-                        "finished run {} ({:.2}s)",
-                        i + 1,
-                        run_start.elapsed().as_secs_f32()
-                    );
+        let elapsed_time = simulation_start.elapsed().as_secs_f32();
+        log_info!("simulation complete ({elapsed_time:.2}s).");
+
+        let (best_cost, best_sequence) = aggregate
+            .best_run
+            .map_or((None, vec![]), |(cost, sequence)| (Some(cost), sequence));
+        let runs = aggregate.run_costs.len() as u32;
+        let failures = runs - aggregate.successes;
+        let report = SimulationReport {
+            runs,
+            starting_item,
+            successes: aggregate.successes,
+            expected_salvage: aggregate.total_salvage / failures.max(1) as f32,
+            best_cost,
+            best_sequence,
+            cost_distribution: CostDistribution::from_run_costs(&aggregate.run_costs),
+        };
+        report.display(&self.prices, &self.report_currency);
+        self.notify(|o| o.on_simulation_finished(&report));
+        report
+    }
+
+    /// Plans crafting `target_state`, either by crafting it directly or by
+    /// crafting two halves of it and recombining them, up to `max_depth`
+    /// levels of recombination. Each crafted step is estimated over `runs`.
+    pub fn plan_craft(
+        &self,
+        target_state: &ItemState,
+        runs: u32,
+        steps_per_run: u32,
+        max_depth: u8,
+    ) -> CraftPlan {
+        let planning_start = Instant::now();
+        log_info!("planning crafts for the target (up to {max_depth} recombinations deep).");
+        self.notify(|o| o.on_plan_started(target_state));
+
+        let solve_budget = self.start_solve_budget();
+        let mut crafted_plans = HashMap::new();
+        let plan = self.plan_craft_step(
+            target_state,
+            runs,
+            steps_per_run,
+            max_depth,
+            &mut crafted_plans,
+            &solve_budget,
+        );
+
+        let elapsed_time = planning_start.elapsed().as_secs_f32();
+        log_info!("planning complete ({elapsed_time:.2}s).");
+        self.notify(|o| o.on_plan_finished(&plan));
+        plan
+    }
+
+    /// Solves for the crafting strategy with the highest chance of attaining
+    /// `target_state` without spending more than `budget` (in the base
+    /// currency), retrying from a new item whenever a run fails. Every
+    /// strategy is evaluated over `runs` crafting sessions.
+    pub fn solve_within_budget(
+        &self,
+        target_state: &ItemState,
+        budget: f32,
+        runs: u32,
+        steps_per_run: u32,
+    ) -> BudgetReport {
+        let solve_start = Instant::now();
+        log_info!(
+            "solving for the best strategy within {}.",
+            self.prices.format_cost(budget, &self.report_currency)
+        );
+        self.notify(|o| o.on_budget_started(target_state, budget));
+
+        // sessions continue past the budget to measure the tail of the costs
+        let max_cost = budget * BUDGET_TAIL_MULTIPLIER;
+        let solve_budget = self.start_solve_budget();
+        // the rest of the budget is reserved for the runs themselves
+        let starting_budget = solve_budget.share(STARTING_ITEM_BUDGET_SHARE);
+        let starting_item =
+            self.choose_starting_item_within(target_state, runs, steps_per_run, &starting_budget);
+
+        // every strategy is a set of allowed actions, starting with all of them
+        let mut strategies: Vec<Vec<String>> = vec![
+            self.craft_actions
+                .craft_actions
+                .iter()
+                .map(|ca| ca.id.clone())
+                .collect(),
+        ];
+        let sessions = self.run_budget_sessions(
+            target_state,
+            &starting_item,
+            runs,
+            steps_per_run,
+            max_cost,
+            None,
+            &solve_budget,
+        );
+        for (_, sequence) in sessions.iter().filter(|(cost, _)| *cost <= budget) {
+            let Some(mut actions) = sequence.clone() else {
+                continue;
+            };
+            actions.sort();
+            actions.dedup();
+            if !strategies.contains(&actions) {
+                strategies.push(actions);
+            }
+        }
+
+        let mut best_report: Option<BudgetReport> = None;
+        for actions in strategies.into_iter().take(MAX_BUDGET_STRATEGIES) {
+            // the first strategy is always evaluated, so there is a best report
+            if best_report.is_some() && solve_budget.is_exhausted() {
+                break;
+            }
+
+            let action_ids = actions
+                .iter()
+                .filter_map(|a| self.id_registry.actions.get_id(a))
+                .collect::<Vec<InternedId>>();
+            let sessions = self.run_budget_sessions(
+                target_state,
+                &starting_item,
+                runs,
+                steps_per_run,
+                max_cost,
+                Some(&action_ids),
+                &solve_budget,
+            );
+            // a strategy that was stopped before any sessions never succeeds
+            let success_chance = if sessions.is_empty() {
+                0.0
+            } else {
+                sessions
+                    .iter()
+                    .filter(|(cost, sequence)| sequence.is_some() && *cost <= budget)
+                    .count() as f32
+                    / sessions.len() as f32
+            };
+            let cost_distribution =
+                CostDistribution::from_costs(sessions.into_iter().map(|(cost, _)| cost).collect());
+            log_debug!(
+                "strategy {actions:?} succeeds {:.2}% of the time.",
+                success_chance * 100.0
+            );
+
+            let is_better_report = best_report.as_ref().is_none_or(|br| {
+                success_chance > br.success_chance
+                    || (success_chance == br.success_chance
+                        && cost_distribution.get_mean() < br.cost_distribution.get_mean())
+            });
+            if is_better_report {
+                best_report = Some(BudgetReport {
+                    budget,
+                    actions,
+                    success_chance,
+                    cost_distribution,
                 });
             }
-        });
+        }
 
-        // lock the shared variables one last time to read the final results
-        let final_best_cost = best_cost.lock().unwrap();
-        let final_best_sequence = best_sequence.lock().unwrap();
+        let elapsed_time = solve_start.elapsed().as_secs_f32();
+        log_info!("solving complete ({elapsed_time:.2}s).");
+        let best_report = best_report.expect("there is always at least one budget strategy");
+        self.notify(|o| o.on_budget_finished(&best_report));
+        best_report
+    }
 
-        // the simulation is complete after the mutable members are successfully unlocked
-        let elapsed_time = simulation_start.elapsed().as_secs_f32();
-        log_info!("simulation complete ({elapsed_time:.2}s).");
+    /// Runs `runs` crafting sessions in parallel, each retrying from a new
+    /// item until `target_state` is met or `max_cost` is spent, and returns
+    /// the cost of each session with its final successful sequence, if it
+    /// succeeded. Sessions that spend `max_cost` cost at least `max_cost`, and
+    /// sessions stopped by the `solve_budget` fail at what they spent so far.
+    #[allow(clippy::too_many_arguments)]
+    fn run_budget_sessions(
+        &self,
+        target_state: &ItemState,
+        starting_item: &StartingItem,
+        runs: u32,
+        steps_per_run: u32,
+        max_cost: f32,
+        allowed_actions: Option<&[InternedId]>,
+        solve_budget: &SolveBudget,
+    ) -> Vec<(f32, Option<Vec<String>>)> {
+        worker_pool::run_pooled(runs, Vec::new, |sessions, _| {
+            let mut spent = 0.0;
+            loop {
+                if spent >= max_cost {
+                    sessions.push((spent.max(max_cost), None));
+                    return;
+                }
+                if !solve_budget.try_iteration() {
+                    sessions.push((spent, None));
+                    return;
+                }
+                let run = self.run_simulation(
+                    target_state,
+                    starting_item,
+                    steps_per_run,
+                    max_cost - spent,
+                    allowed_actions,
+                );
+                // any item left over from a failed run is sold
+                spent += run.get_net_cost();
+                if run.succeeded {
+                    sessions.push((spent, Some(run.sequence)));
+                    return;
+                }
+                // a run that can't spend anything, or sells for what it
+                // cost, will never succeed or spend the session out
+                if run.get_net_cost() <= 0.0 {
+                    sessions.push((spent.max(max_cost), None));
+                    return;
+                }
+            }
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Simulates a single run of crafting towards `target_state`, starting
+    /// from a bought `starting_item`. The run stops before spending more than
+    /// `max_cost`, not even buying the starting item if it costs more, and
+    /// only uses `allowed_actions` if there are any.
+    fn run_simulation(
+        &self,
+        target_state: &ItemState,
+        starting_item: &StartingItem,
+        steps_per_run: u32,
+        max_cost: f32,
+        allowed_actions: Option<&[InternedId]>,
+    ) -> SimulationRun {
+        let mut crafted_item = starting_item.item.clone();
+        if starting_item.price > max_cost {
+            log_debug!("can't afford the starting item!");
+            return SimulationRun {
+                item: crafted_item,
+                sequence: vec![],
+                cost: 0.0,
+                salvage: 0.0,
+                succeeded: false,
+            };
+        }
+
+        let mut current_cost = starting_item.price;
+        // a bought starting item may already meet the target
+        let mut succeeded = crafted_item.meets_target(target_state);
+        let mut sequence: Vec<InternedId> = Vec::new();
+
+        let mut rng = rand::rng();
 
-        if final_best_sequence.is_empty() {
-            log_info!("could not find a successful sequence within the given parameters.");
+        // apply `steps_per_run` amount of crafts for each run
+        for _ in 0..steps_per_run {
+            if succeeded {
+                break;
+            }
+            let good_action_ids = self
+                .get_crafting_actions(target_state, &crafted_item)
+                .into_iter()
+                .filter(|id| allowed_actions.is_none_or(|aa| aa.contains(id)))
+                .collect::<Vec<InternedId>>();
+
+            if good_action_ids.is_empty() {
+                log_debug!("can't find any good crafting actions!");
+                break;
+            }
+
+            // a cheaper action can still be afforded when another can't
+            let affordable_action_ids = good_action_ids
+                .into_iter()
+                .filter(|id| current_cost + self.get_action_cost(*id) <= max_cost)
+                .collect::<Vec<InternedId>>();
+            let Some(action_id) = affordable_action_ids.choose(&mut rng).copied() else {
+                log_debug!("can't afford any more crafting actions!");
+                break;
+            };
+            let action = self
+                .get_craft_action(action_id)
+                .expect("Unknown crafting action");
+
+            self.apply_crafting_action(&mut crafted_item, action, target_state);
+            self.notify(|o| o.on_action_applied(&action.id, &crafted_item));
+            current_cost += action.get_cost(&self.prices);
+            sequence.push(action_id);
+
+            if crafted_item.meets_target(target_state) {
+                succeeded = true;
+                break;
+            }
+
+            // clear out any actions from the crafted item's target
+            self.reset_item_target_action(&mut crafted_item, &action.id);
+        }
+
+        // an item left over from a failed run can still be sold
+        let salvage = if succeeded {
+            0.0
         } else {
-            log_info!("optimal sequence found: {:?}", *final_best_sequence);
-            log_info!("total cost: ~{:.2} exalted orbs", *final_best_cost);
+            self.valuations
+                .as_ref()
+                .map_or(0.0, |v| v.get_item_value(&crafted_item, &self.prices))
+        };
+
+        SimulationRun {
+            item: crafted_item,
+            sequence: sequence
+                .into_iter()
+                .map(|id| self.id_registry.actions.get_name(id).to_owned())
+                .collect(),
+            cost: current_cost,
+            salvage,
+            succeeded,
+        }
+    }
+
+    /// Plans a single step of crafting `target_state`, choosing the cheapest
+    /// of crafting it directly or recombining any split of its modifiers.
+    fn plan_craft_step(
+        &self,
+        target_state: &ItemState,
+        runs: u32,
+        steps_per_run: u32,
+        depth: u8,
+        crafted_plans: &mut HashMap<CompactItemState, CraftPlan>,
+        solve_budget: &SolveBudget,
+    ) -> CraftPlan {
+        let mut best_plan = self.plan_crafted_item(
+            target_state,
+            runs,
+            steps_per_run,
+            crafted_plans,
+            solve_budget,
+        );
+        if depth == 0 {
+            return best_plan;
+        }
+
+        let target_mods = [
+            target_state.prefixes.as_slice(),
+            target_state.suffixes.as_slice(),
+        ]
+        .concat();
+        for (left_target, right_target) in target_state.get_target_splits() {
+            if solve_budget.is_exhausted() {
+                break;
+            }
+
+            let left_plan = self.plan_craft_step(
+                &left_target,
+                runs,
+                steps_per_run,
+                depth - 1,
+                crafted_plans,
+                solve_budget,
+            );
+            let right_plan = self.plan_craft_step(
+                &right_target,
+                runs,
+                steps_per_run,
+                depth - 1,
+                crafted_plans,
+                solve_budget,
+            );
+
+            let left_mods = [
+                left_target.prefixes.as_slice(),
+                left_target.suffixes.as_slice(),
+            ]
+            .concat();
+            let right_mods = [
+                right_target.prefixes.as_slice(),
+                right_target.suffixes.as_slice(),
+            ]
+            .concat();
+            let outcomes =
+                self.get_recombine_outcomes(&left_target, &right_target, &left_mods, &right_mods);
+            let target_chance = self.get_recombine_target_chance(&outcomes, target_state);
+
+            // both halves must be crafted again for every recombine attempt
+            let expected_cost = if target_chance > 0.0 {
+                (left_plan.expected_cost + right_plan.expected_cost) / target_chance
+            } else {
+                f32::INFINITY
+            };
+
+            if expected_cost < best_plan.expected_cost {
+                log_debug!(
+                    "recombining {} modifiers is cheaper (cost: {:.2}).",
+                    target_mods.len(),
+                    expected_cost
+                );
+                let cost_distribution = CostDistribution::from_recombining(
+                    &left_plan.cost_distribution,
+                    &right_plan.cost_distribution,
+                    target_chance,
+                    runs,
+                );
+                best_plan = CraftPlan {
+                    target: target_state.clone(),
+                    step: CraftStep::Recombine {
+                        left: Box::new(left_plan),
+                        right: Box::new(right_plan),
+                        left_mods,
+                        right_mods,
+                    },
+                    success_chance: target_chance,
+                    expected_cost,
+                    cost_distribution,
+                };
+            }
+        }
+
+        best_plan
+    }
+
+    /// Chooses the cheapest item to start crafting `target_state` from, out
+    /// of a new base and any [`Solver::starting_items`], estimating each of
+    /// their expected costs over `runs`.
+    pub fn choose_starting_item(
+        &self,
+        target_state: &ItemState,
+        runs: u32,
+        steps_per_run: u32,
+    ) -> StartingItem {
+        let solve_budget = self.start_solve_budget();
+        self.choose_starting_item_within(target_state, runs, steps_per_run, &solve_budget)
+    }
+
+    /// Chooses the cheapest item to start crafting `target_state` from, within
+    /// the `solve_budget` of a solve.
+    fn choose_starting_item_within(
+        &self,
+        target_state: &ItemState,
+        runs: u32,
+        steps_per_run: u32,
+        solve_budget: &SolveBudget,
+    ) -> StartingItem {
+        let mut starting_items = self.get_starting_items(target_state);
+        if starting_items.len() == 1 {
+            return starting_items.remove(0);
         }
+
+        let crafted_plan = self.plan_crafted_item(
+            target_state,
+            runs,
+            steps_per_run,
+            &mut HashMap::new(),
+            solve_budget,
+        );
+        let CraftStep::Craft { start, .. } = crafted_plan.step else {
+            unreachable!("crafted items are always planned from a starting item");
+        };
+        log_info!(
+            "starting from a {} bought for {}.",
+            start.describe(),
+            self.prices.format_cost(start.price, &self.report_currency)
+        );
+        start
     }
 
+    /// Plans crafting `target_state` directly from the cheapest starting item,
+    /// estimating its expected cost over `runs`.
+    fn plan_crafted_item(
+        &self,
+        target_state: &ItemState,
+        runs: u32,
+        steps_per_run: u32,
+        crafted_plans: &mut HashMap<CompactItemState, CraftPlan>,
+        solve_budget: &SolveBudget,
+    ) -> CraftPlan {
+        let compact_target = self.compact_item(target_state);
+        if let Some(crafted_plan) = compact_target.as_ref().and_then(|ct| crafted_plans.get(ct)) {
+            return crafted_plan.clone();
+        }
+
+        let crafted_plan = self
+            .get_starting_items(target_state)
+            .into_iter()
+            .map(|si| {
+                self.plan_crafted_item_from(target_state, si, runs, steps_per_run, solve_budget)
+            })
+            .min_by(|a, b| a.expected_cost.total_cmp(&b.expected_cost))
+            .expect("there is always a new base to start crafting from");
+
+        // a plan cut short by the budget isn't an estimate worth reusing
+        if let Some(compact_target) = compact_target
+            && !solve_budget.is_exhausted()
+        {
+            crafted_plans.insert(compact_target, crafted_plan.clone());
+        }
+        crafted_plan
+    }
+
+    /// Plans crafting `target_state` directly from `starting_item`, estimating
+    /// its expected cost over `runs`, or as many as the `solve_budget` allows.
+    fn plan_crafted_item_from(
+        &self,
+        target_state: &ItemState,
+        starting_item: StartingItem,
+        runs: u32,
+        steps_per_run: u32,
+        solve_budget: &SolveBudget,
+    ) -> CraftPlan {
+        let simulation_runs = worker_pool::run_pooled(runs, Vec::new, |simulation_runs, _| {
+            if solve_budget.try_iteration() {
+                simulation_runs.push(self.run_simulation(
+                    target_state,
+                    &starting_item,
+                    steps_per_run,
+                    f32::INFINITY,
+                    None,
+                ));
+            }
+        })
+        .into_iter()
+        .flatten()
+        .collect::<Vec<SimulationRun>>();
+
+        let successful_runs = simulation_runs.iter().filter(|r| r.succeeded);
+        let successes = successful_runs.clone().count();
+        let total_cost = simulation_runs
+            .iter()
+            .map(|r| r.get_net_cost())
+            .sum::<f32>();
+        let best_sequence = successful_runs
+            .min_by(|a, b| a.cost.total_cmp(&b.cost))
+            .map(|r| r.sequence.clone())
+            .unwrap_or_default();
+
+        // failed runs are sold and retried, so their net cost is spread across
+        // every success
+        CraftPlan {
+            target: target_state.clone(),
+            step: CraftStep::Craft {
+                start: starting_item,
+                actions: best_sequence,
+            },
+            success_chance: successes as f32 / simulation_runs.len().max(1) as f32,
+            expected_cost: if successes > 0 {
+                total_cost / successes as f32
+            } else {
+                f32::INFINITY
+            },
+            cost_distribution: CostDistribution::from_run_costs(
+                &simulation_runs
+                    .iter()
+                    .map(|r| (r.get_net_cost(), r.succeeded))
+                    .collect::<Vec<(f32, bool)>>(),
+            ),
+        }
+    }
+
+    /// Recombines `left_item` and `right_item`, keeping some of the chosen
+    /// `left_mods` and `right_mods`, and returns the recombined item, or
+    /// nothing if recombining failed or the items can't be recombined.
     pub fn recombine(
         &self,
         left_item: &ItemState,
         right_item: &ItemState,
         left_mods: Vec<Modifier>,
         right_mods: Vec<Modifier>,
-    ) -> ItemState {
-        let base_item = self.select_recombine_item(left_item, right_item, &left_mods, &right_mods);
-        let all_mods = [left_mods.as_slice(), right_mods.as_slice()].concat();
+    ) -> Option<ItemState> {
+        let Some(recombination) = self
+            .recombinations
+            .get_recombination_for_class(&self.items, &left_item.class)
+        else {
+            log_warning!(
+                "cannot find recombination rules for '{}', check the recombination.toml file!",
+                left_item.class
+            );
+            return None;
+        };
+        let base_item = self.select_recombine_item(
+            recombination,
+            left_item,
+            right_item,
+            &left_mods,
+            &right_mods,
+        );
+        let item_level =
+            self.get_recombined_item_level(recombination, &base_item, left_item, right_item);
         let class_tiers = self.get_class_tiers_for_item(&base_item).expect(
             "cannot find class tiers for the selected item, check the class_tiers.toml file!",
         );
 
-        // let amount_of_modifiers = self.get_amount_of_modifers(all_mods.len().try_into().unwrap());
-        // log_info!(
-        //     "{amount_of_modifiers} modifiers chosen out of {} total mods.",
-        //     all_mods.len()
-        // );
+        let (prefixes, suffixes) =
+            self.get_selected_modifiers(left_item, right_item, &left_mods, &right_mods);
+        let modifier_count = (prefixes.len() + suffixes.len()) as u8;
 
-        let left_prefixes = left_item
-            .prefixes
-            .clone()
-            .into_iter()
-            .filter(|p| left_mods.iter().any(|m| m.id.eq(&p.id)))
-            .collect::<Vec<Modifier>>();
-        let left_suffixes = left_item
-            .suffixes
-            .clone()
-            .into_iter()
-            .filter(|p| left_mods.iter().any(|m| m.id.eq(&p.id)))
-            .collect::<Vec<Modifier>>();
-        let right_prefixes = right_item
-            .prefixes
-            .clone()
-            .into_iter()
-            .filter(|p| right_mods.iter().any(|m| m.id.eq(&p.id)))
-            .collect::<Vec<Modifier>>();
-        let right_suffixes = right_item
-            .suffixes
-            .clone()
-            .into_iter()
-            .filter(|p| right_mods.iter().any(|m| m.id.eq(&p.id)))
-            .collect::<Vec<Modifier>>();
-
-        let prefixes = [left_prefixes.as_slice(), right_prefixes.as_slice()].concat();
-        let suffixes = [left_suffixes.as_slice(), right_suffixes.as_slice()].concat();
+        let amount_of_modifiers = self.get_amount_of_modifers(recombination, modifier_count);
+        log_info!("{amount_of_modifiers} modifiers chosen out of {modifier_count} total mods.");
 
-        let modifiers =
-            self.pick_from_selected_modifiers(all_mods.len() as u8, &prefixes, &suffixes);
+        let (prefixes, suffixes) = self.pick_from_selected_modifiers(
+            &self.items.get_affix_slots(&base_item.base, Rarity::Rare),
+            amount_of_modifiers,
+            &prefixes,
+            &suffixes,
+        );
+        let modifiers = [prefixes.as_slice(), suffixes.as_slice()].concat();
 
         log_info!(
             "modifiers selected:\n{}",
@@ -197,12 +773,8 @@ impl Solver {
                 .join("\n")
         );
 
-        let success_chance = modifiers
-            .iter()
-            .map(|m| {
-                self.get_modifier_recombine_chance(class_tiers, base_item.item_level, &m.id, m.tier)
-            })
-            .sum::<f32>();
+        let success_chance =
+            self.get_recombine_success_chance(class_tiers, recombination, item_level, &modifiers);
 
         log_info!(
             "while recombining, success chance is {:.2}%",
@@ -211,20 +783,231 @@ impl Solver {
 
         if randomizer::if_more_than(success_chance, false, true) {
             log_info!("recombining succeeded!");
+            let recombined_slots =
+                self.get_recombined_slots(&self.items, &base_item.base, &prefixes, &suffixes);
+            let recombined_item = ItemState::new(
+                &base_item.base,
+                &base_item.class,
+                recombined_slots,
+                item_level,
+                prefixes,
+                suffixes,
+            );
+            Some(recombined_item)
         } else {
             log_info!("recombining failed.");
+            None
         }
+    }
+
+    /// Gets every possible outcome of recombining `left_item` and `right_item`
+    /// with the chosen `left_mods` and `right_mods`, with exact probabilities.
+    pub fn get_recombine_outcomes(
+        &self,
+        left_item: &ItemState,
+        right_item: &ItemState,
+        left_mods: &[Modifier],
+        right_mods: &[Modifier],
+    ) -> Vec<RecombineOutcome> {
+        self.enumerate_recombine_outcomes(
+            &self.items,
+            &self.recombinations.recombinations,
+            &self.class_tiers.class_tiers,
+            left_item,
+            right_item,
+            left_mods,
+            right_mods,
+        )
+    }
+
+    /// Plans which two of the owned `items` to recombine, and which of their
+    /// modifiers to carry, to maximize the chance of meeting `target_state`.
+    pub fn plan_recombination(
+        &self,
+        target_state: &ItemState,
+        items: &[ItemState],
+    ) -> Option<RecombinePlan> {
+        Recombiner::plan_recombination(
+            self,
+            &self.items,
+            &self.recombinations.recombinations,
+            &self.class_tiers.class_tiers,
+            target_state,
+            items,
+        )
+    }
+
+    /// Parses in-game item text, as copied with Ctrl+C, into an [`ItemState`].
+    pub fn parse_item(&self, text: &str) -> Result<ItemState, ItemTextError> {
+        self.parse_item_text(
+            &self.items,
+            &self.class_tiers.class_tiers,
+            &self.modifiers,
+            text,
+        )
+    }
+
+    /// Formats `item_state` as in-game item text.
+    pub fn format_item(&self, item_state: &ItemState) -> String {
+        self.format_item_text(&self.items, &self.modifiers, item_state)
+    }
+
+    /// Activates an omen by `omen_id` on `item_state` without paying for it,
+    /// e.g.: for an omen that is already active on an item being held.
+    pub fn activate_omen(&self, item_state: &mut ItemState, omen_id: &str) {
+        let omen = self.find_craft_action(omen_id).expect("Unknown omen");
+        for outcome in omen
+            .outcomes
+            .iter()
+            .filter(|o| o.action == OutcomeAction::Target)
+        {
+            self.apply_target_to_item(omen, outcome, item_state);
+        }
+    }
+
+    /// Recommends which crafting action to take next on `current_item` to
+    /// attain `target_state`, with the expected remaining cost and chance of
+    /// success after each action over `runs`, best first.
+    pub fn recommend_next_actions(
+        &self,
+        current_item: &ItemState,
+        target_state: &ItemState,
+        runs: u32,
+        steps_per_run: u32,
+    ) -> Vec<ActionRecommendation> {
+        if current_item.meets_target(target_state) {
+            log_info!("the current item already meets the target.");
+            return vec![];
+        }
+
+        let recommend_start = Instant::now();
+        let solve_budget = self.start_solve_budget();
+        log_info!("recommending the next crafting action for the current item.");
+        self.notify(|o| o.on_recommend_started(current_item));
+
+        let mut recommendations = self
+            .get_crafting_actions(target_state, current_item)
+            .into_iter()
+            .filter_map(|action_id| {
+                let action = self.get_craft_action(action_id)?;
+                let run_costs = worker_pool::run_pooled(runs, Vec::new, |run_costs, _| {
+                    if !solve_budget.try_iteration() {
+                        return;
+                    }
+                    // take the action first, then continue crafting as usual
+                    let mut crafted_item = current_item.clone();
+                    self.apply_crafting_action(&mut crafted_item, action, target_state);
+                    self.notify(|o| o.on_action_applied(&action.id, &crafted_item));
+                    self.reset_item_target_action(&mut crafted_item, &action.id);
+                    let starting_item =
+                        StartingItem::new(crafted_item, action.get_cost(&self.prices));
+                    let run = self.run_simulation(
+                        target_state,
+                        &starting_item,
+                        steps_per_run.saturating_sub(1),
+                        f32::INFINITY,
+                        None,
+                    );
+                    run_costs.push((run.get_net_cost(), run.succeeded));
+                })
+                .into_iter()
+                .flatten()
+                .collect::<Vec<(f32, bool)>>();
+                // actions that were stopped before any runs can't be recommended
+                if run_costs.is_empty() {
+                    return None;
+                }
+
+                let successes = run_costs.iter().filter(|(_, succeeded)| *succeeded).count();
+                let total_cost = run_costs.iter().map(|(cost, _)| cost).sum::<f32>();
+                Some(ActionRecommendation {
+                    action: action.id.clone(),
+                    success_chance: successes as f32 / run_costs.len() as f32,
+                    expected_cost: total_cost / run_costs.len() as f32,
+                })
+            })
+            .collect::<Vec<ActionRecommendation>>();
+        recommendations.sort_by(|a, b| {
+            a.get_cost_per_success()
+                .total_cmp(&b.get_cost_per_success())
+                .then(b.success_chance.total_cmp(&a.success_chance))
+        });
 
-        base_item
+        let elapsed_time = recommend_start.elapsed().as_secs_f32();
+        log_info!("recommending complete ({elapsed_time:.2}s).");
+        self.notify(|o| o.on_recommend_finished(&recommendations));
+        recommendations
     }
 
-    /// Applies a crafting action to an item.
-    fn apply_crafting_action(&self, item_state: &mut ItemState, action_id: &str) {
-        // get the crafting action by id
-        let action = self
+    /// Gets the ids of every crafting action that can be used on `item_state`.
+    pub fn get_valid_actions(&self, item_state: &ItemState) -> Vec<String> {
+        self.craft_actions
             .craft_actions
-            .get_action_by_id(action_id)
-            .expect("Unknown crafting action");
+            .iter()
+            .filter(|ca| self.is_valid_crafting_action(&self.items, ca, item_state))
+            .map(|ca| ca.id.clone())
+            .collect()
+    }
+
+    /// Applies a crafting action by `action_id` to `item_state`, making any
+    /// choices in favor of `target_state`, and returns if it could be used.
+    pub fn apply_action(
+        &self,
+        item_state: &mut ItemState,
+        action_id: &str,
+        target_state: &ItemState,
+    ) -> bool {
+        let Some(action) = self
+            .find_craft_action(action_id)
+            .filter(|ca| self.is_valid_crafting_action(&self.items, ca, item_state))
+        else {
+            return false;
+        };
+
+        self.apply_crafting_action(item_state, action, target_state);
+        self.reset_item_target_action(item_state, action_id);
+        self.notify(|o| o.on_action_applied(action_id, item_state));
+        true
+    }
+
+    /// Compacts `item_state` into interned ids, or `None` if it has ids that
+    /// aren't in the datasets.
+    pub fn compact_item(&self, item_state: &ItemState) -> Option<CompactItemState> {
+        CompactItemState::from_item(item_state, &self.id_registry)
+    }
+
+    /// Expands a `compact_item` back into an [`ItemState`].
+    pub fn expand_item(&self, compact_item: &CompactItemState) -> ItemState {
+        compact_item.to_item(&self.id_registry)
+    }
+
+    /// Gets a crafting action by its interned `action` id.
+    fn get_craft_action(&self, action: InternedId) -> Option<&CraftAction> {
+        let index = self.id_registry.get_action_index(action)?;
+        self.craft_actions.craft_actions.get(index)
+    }
+
+    /// Finds a crafting action by `action_id` through its interned id.
+    fn find_craft_action(&self, action_id: &str) -> Option<&CraftAction> {
+        self.get_craft_action(self.id_registry.actions.get_id(action_id)?)
+    }
+
+    /// Gets the cost of a crafting `action` at current prices, in the base
+    /// currency.
+    fn get_action_cost(&self, action: InternedId) -> f32 {
+        self.get_craft_action(action)
+            .map(|ca| ca.get_cost(&self.prices))
+            .unwrap_or_default()
+    }
+
+    /// Applies a crafting `action` to an item, making any choices in favor of
+    /// `target_state`.
+    fn apply_crafting_action(
+        &self,
+        item_state: &mut ItemState,
+        action: &CraftAction,
+        target_state: &ItemState,
+    ) {
         log_debug!("using '{}'...", action.name);
 
         // get an outcome from the crafting action
@@ -239,11 +1022,9 @@ impl Solver {
         .expect("Could not distribute modifier weights.");
         let outcome = &action.outcomes[dist.sample(&mut rng)];
 
-        // apply the outcome to the item
-        self.apply_outcome_to_item(&action, outcome, item_state);
-
-        // Update rarity based on the new number of affixes
-        self.update_item_rarity(item_state, action_id);
+        // upgrade the rarity before any affixes are added, then apply the outcome
+        self.upgrade_item_rarity(&self.items, action, item_state);
+        self.apply_outcome_to_item(action, outcome, item_state, target_state);
     }
 
     /// Applies a [`CraftOutcome`] to an [`ItemState`].
@@ -252,80 +1033,94 @@ impl Solver {
         action: &CraftAction,
         outcome: &CraftOutcome,
         item_state: &mut ItemState,
+        target_state: &ItemState,
     ) {
-        match outcome.action.as_str() {
-            "add" => {
+        match outcome.action {
+            OutcomeAction::Add => {
                 for _ in 0..outcome.count.unwrap_or(1) {
-                    let outcome_affix = self.get_outcome_affix(outcome, item_state);
-                    if outcome_affix.is_empty() {
+                    let Some(outcome_affix) = self.get_outcome_affix(outcome, item_state) else {
                         log_debug!("couldn't find a good \"add\" outcome!");
                         break;
-                    }
+                    };
                     if self.add_random_affix(
-                        &self.items.items,
-                        &self.class_tiers.class_tiers,
-                        &self.modifiers,
+                        &self.items,
+                        &self.crafting_index,
+                        &self.id_registry,
                         item_state,
                         outcome_affix,
                         1,
                     ) {
-                        item_state.clear_affix_target(outcome_affix.to_owned());
+                        item_state.clear_affix_target(outcome_affix);
                     }
                 }
             }
-            "remove" => {
+            OutcomeAction::Remove if item_state.has_targeted_desecrated() => {
+                self.remove_desecrated_affix(item_state);
+            }
+            OutcomeAction::Remove => {
                 for _ in 0..outcome.count.unwrap_or(1) {
-                    let outcome_affix = self.get_outcome_affix(outcome, item_state);
-                    if outcome_affix.is_empty() {
+                    let Some(outcome_affix) = self.get_outcome_affix(outcome, item_state) else {
                         log_debug!("couldn't find a good \"remove\" outcome!");
                         break;
-                    }
+                    };
                     if self.remove_random_affix(item_state, outcome_affix, 1) {
-                        item_state.clear_affix_target(outcome_affix.to_owned());
+                        item_state.clear_affix_target(outcome_affix);
                     }
                 }
             }
-            "replace" => {
-                let target_affix = self.get_outcome_affix(outcome, item_state);
-                self.remove_random_affix(item_state, target_affix, 1);
+            OutcomeAction::Replace => {
+                let Some(target_affix) = self.get_outcome_affix(outcome, item_state) else {
+                    log_debug!("couldn't find a good \"replace\" outcome!");
+                    return;
+                };
+                if !self.remove_random_affix(item_state, target_affix, 1) {
+                    return;
+                }
                 self.add_random_affix(
-                    &self.items.items,
-                    &self.class_tiers.class_tiers,
-                    &self.modifiers,
+                    &self.items,
+                    &self.crafting_index,
+                    &self.id_registry,
                     item_state,
                     target_affix,
                     1,
                 );
-                item_state.clear_affix_target(target_affix.to_owned());
+                item_state.clear_affix_target(target_affix);
             }
-            "target" => {
-                if action.targets_lowest_tier() {
-                    item_state.target_lowest_tier();
-                }
-                if action.targets_affix() {
-                    item_state.target_affixes(&outcome.affix);
+            OutcomeAction::Desecrate => match self.get_outcome_affix(outcome, item_state) {
+                Some(outcome_affix) => {
+                    if self.add_unrevealed_affix(item_state, outcome_affix) {
+                        item_state.clear_affix_target(outcome_affix);
+                    }
                 }
-                item_state.set_next_action(action.currency.clone());
+                None => log_debug!("couldn't find a good \"desecrate\" outcome!"),
+            },
+            OutcomeAction::Reveal => {
+                self.reveal_unrevealed_affix(
+                    &self.crafting_index,
+                    &self.id_registry,
+                    item_state,
+                    outcome.count.unwrap_or(1),
+                    target_state,
+                );
             }
-            _ => log_debug!("Unknown action type: {}", outcome.action),
+            OutcomeAction::Target => self.apply_target_to_item(action, outcome, item_state),
         }
     }
 
-    /// Updates [`ItemState::rarity`].
-    fn update_item_rarity(&self, item_state: &mut ItemState, action_id: &str) {
-        let num_affixes = item_state.prefixes.len() + item_state.suffixes.len();
-        if !item_state.rarity.eq("rare") {
-            item_state.rarity = if num_affixes == 0 && !item_state.rarity.eq("magic") {
-                "normal".to_owned()
-            } else if num_affixes >= 3 || action_id.eq("regal") {
-                "rare".to_owned()
-            } else if num_affixes > 0 && num_affixes < 3 {
-                "magic".to_owned()
-            } else {
-                item_state.rarity.to_owned()
-            };
-            log_debug!("updated item rarity to {}", item_state.rarity);
+    /// Applies a "target" [`CraftOutcome`] of an omen to an [`ItemState`].
+    fn apply_target_to_item(
+        &self,
+        action: &CraftAction,
+        outcome: &CraftOutcome,
+        item_state: &mut ItemState,
+    ) {
+        if action.targets_lowest_tier() {
+            item_state.target_lowest_tier();
         }
+        if action.targets_affix() {
+            item_state.target_affixes(outcome.affix);
+        }
+        item_state.set_next_action(action.currency.clone());
     }
 
     /// Gets a collection of "good" crafting action ids based on `crafted_item`
@@ -334,44 +1129,224 @@ impl Solver {
         &self,
         target_state: &ItemState,
         crafted_item: &ItemState,
-    ) -> Vec<String> {
-        let good_modifiers = crafted_item.get_good_modifiers(target_state);
-        let good_actions = if !good_modifiers.is_empty() {
-            self.craft_actions
-                .get_actions_except(
-                    vec!["remove".to_owned(), "replace".to_owned()].as_slice(),
-                    &good_modifiers
-                        .iter()
-                        .map(|gm| gm.0.to_string())
-                        .collect::<Vec<String>>(),
-                )
-                .into_iter()
-                .filter(|ca| self.is_valid_crafting_action(ca, crafted_item))
-                .collect::<Vec<CraftAction>>()
-        } else {
-            self.craft_actions
-                .craft_actions
-                .clone()
-                .into_iter()
-                .filter(|ca| self.is_valid_crafting_action(ca, crafted_item))
-                .collect()
-        };
+    ) -> Vec<InternedId> {
+        // actions that could remove a good modifier are never good
+        let good_affixes = crafted_item
+            .get_good_modifiers(target_state)
+            .keys()
+            .map(|affix_side| OutcomeAffix::from(*affix_side))
+            .collect::<Vec<OutcomeAffix>>();
 
-        good_actions
-            .iter()
-            .map(|ca| ca.id.to_owned())
-            .collect::<Vec<_>>()
+        self.id_registry
+            .get_action_ids()
+            .filter(|id| {
+                self.get_craft_action(*id).is_some_and(|ca| {
+                    !ca.does_any_affix_action(
+                        &[OutcomeAction::Remove, OutcomeAction::Replace],
+                        &good_affixes,
+                    ) && self.is_valid_crafting_action(&self.items, ca, crafted_item)
+                })
+            })
+            .collect()
+    }
+
+    /// Gets every item that crafting `target_state` can start from, a new base
+    /// first.
+    fn get_starting_items(&self, target_state: &ItemState) -> Vec<StartingItem> {
+        let mut starting_items = vec![StartingItem::from_base(&self.items, target_state)];
+        starting_items.extend(
+            self.starting_items
+                .iter()
+                .filter(|si| si.is_candidate_for(target_state))
+                .cloned(),
+        );
+        starting_items
+    }
+
+    /// Starts tracking the time and iterations of a solve against the
+    /// solver's limits and cancellation token.
+    fn start_solve_budget(&self) -> SolveBudget {
+        SolveBudget::new(&self.limits, &self.cancellation_token)
+    }
+
+    /// Notifies every observer of an event.
+    fn notify(&self, event: impl Fn(&dyn SolverObserver)) {
+        for observer in &self.observers {
+            event(observer.as_ref());
+        }
     }
 
     /// Gets all tiers of modifiers for an `item_state`, using it's base class.
     fn get_class_tiers_for_item(&self, item_state: &ItemState) -> Option<&ClassTier> {
+        self.get_class_tier(&item_state.class)
+    }
+
+    /// Gets all tiers of modifiers for an item `class`, including the ones
+    /// of every class it specializes.
+    pub fn get_class_tier(&self, class: &str) -> Option<&ClassTier> {
         self.class_tiers
             .class_tiers
             .iter()
-            .find(|ct| ct.classes.contains(&item_state.class))
+            .find(|ct| ct.classes.iter().any(|c| c.eq(class)))
     }
 }
 
 impl Crafter for Solver {}
 
 impl Recombiner for Solver {}
+
+impl ItemText for Solver {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn target(solver: &Solver) -> ItemState {
+        ItemState::new(
+            "Warlord Cuirass",
+            "chest",
+            solver
+                .items
+                .get_affix_slots("Warlord Cuirass", Rarity::Rare),
+            82,
+            vec![Modifier::from_value(solver, "chest", "max-life", 180)],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn budget_sessions_stop_once_a_bought_item_is_unaffordable() {
+        let solver = Solver::new();
+        let target_state = target(&solver);
+        let starting_item = StartingItem::new(
+            StartingItem::from_base(&solver.items, &target_state).item,
+            10.0,
+        );
+
+        // no allowed actions, so every run only buys the starting item
+        let sessions = solver.run_budget_sessions(
+            &target_state,
+            &starting_item,
+            4,
+            5,
+            25.0,
+            Some(&[]),
+            &solver.start_solve_budget(),
+        );
+
+        assert_eq!(sessions.len(), 4);
+        assert!(
+            sessions
+                .iter()
+                .all(|(cost, sequence)| *cost == 25.0 && sequence.is_none())
+        );
+    }
+
+    #[test]
+    fn simulated_runs_only_use_allowed_actions() {
+        let solver = Solver::new();
+        let target_state = target(&solver);
+        let starting_item = StartingItem::from_base(&solver.items, &target_state);
+        let allowed_actions =
+            ["transmute", "alteration"].map(|a| solver.id_registry.actions.get_id(a).unwrap());
+
+        for _ in 0..20 {
+            let run = solver.run_simulation(
+                &target_state,
+                &starting_item,
+                10,
+                f32::INFINITY,
+                Some(&allowed_actions),
+            );
+            assert!(!run.sequence.is_empty());
+            assert_eq!(run.sequence[0], "transmute");
+            assert!(
+                run.sequence
+                    .iter()
+                    .all(|a| a == "transmute" || a == "alteration")
+            );
+        }
+    }
+
+    #[test]
+    fn budget_sessions_stopped_by_the_solve_budget_fail_at_their_spend() {
+        let mut solver = Solver::new();
+        solver.limits.max_iterations = Some(2);
+        let target_state = target(&solver);
+        let starting_item = StartingItem::new(
+            StartingItem::from_base(&solver.items, &target_state).item,
+            10.0,
+        );
+
+        let sessions = solver.run_budget_sessions(
+            &target_state,
+            &starting_item,
+            1,
+            5,
+            100.0,
+            Some(&[]),
+            &solver.start_solve_budget(),
+        );
+
+        assert_eq!(sessions, vec![(20.0, None)]);
+    }
+
+    #[test]
+    fn only_fractured_modifiers_cant_be_replaced_or_removed() {
+        let solver = Solver::new();
+        let mut item_state = target(&solver);
+        item_state.prefixes[0] = item_state.prefixes[0].clone().as_fractured();
+
+        for action_id in ["chaos", "annul"] {
+            assert!(!solver.apply_action(&mut item_state.clone(), action_id, &target(&solver)));
+        }
+    }
+
+    /// Records the events of a solver.
+    #[derive(Default)]
+    struct RecordingObserver {
+        simulations: Mutex<Vec<u32>>,
+        runs: Mutex<Vec<SimulationRun>>,
+        applied_actions: Mutex<Vec<String>>,
+    }
+
+    impl SolverObserver for RecordingObserver {
+        fn on_simulation_started(&self, runs: u32) {
+            self.simulations.lock().unwrap().push(runs);
+        }
+
+        fn on_run_finished(&self, _run: u32, result: &SimulationRun) {
+            self.runs.lock().unwrap().push(result.clone());
+        }
+
+        fn on_action_applied(&self, action_id: &str, _item_state: &ItemState) {
+            self.applied_actions
+                .lock()
+                .unwrap()
+                .push(action_id.to_owned());
+        }
+    }
+
+    #[test]
+    fn observers_see_every_action_applied_while_simulating() {
+        let mut solver = Solver::new();
+        let observer = Arc::new(RecordingObserver::default());
+        solver.observers.push(observer.clone());
+
+        let report = solver.simulate(&target(&solver), 8, 5);
+
+        assert_eq!(*observer.simulations.lock().unwrap(), vec![8]);
+        let runs = observer.runs.lock().unwrap();
+        assert_eq!(runs.len() as u32, report.runs);
+        let mut sequences = runs
+            .iter()
+            .flat_map(|r| r.sequence.clone())
+            .collect::<Vec<_>>();
+        let mut applied_actions = observer.applied_actions.lock().unwrap().clone();
+        sequences.sort();
+        applied_actions.sort();
+        assert!(!applied_actions.is_empty());
+        assert_eq!(applied_actions, sequences);
+    }
+}
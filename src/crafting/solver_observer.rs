@@ -0,0 +1,51 @@
+use crate::{
+    crafting::{
+        action_recommendation::ActionRecommendation, budget_report::BudgetReport,
+        craft_plan::CraftPlan, simulation_report::SimulationReport, simulation_run::SimulationRun,
+    },
+    items::item_state::ItemState,
+};
+
+/// Responsible for observing the progress of a [`Solver`](crate::crafting::solver::Solver),
+/// e.g.: for user interfaces. Run events only come from simulations, while
+/// applied actions come from every simulated run, including the ones used to
+/// plan or recommend. Run and action events may come from any thread.
+pub trait SolverObserver: Send + Sync {
+    /// Called when a simulation of `runs` starts.
+    fn on_simulation_started(&self, _runs: u32) {}
+
+    /// Called when the simulated run numbered `run` starts.
+    fn on_run_started(&self, _run: u32) {}
+
+    /// Called when the simulated run numbered `run` finishes with `result`.
+    fn on_run_finished(&self, _run: u32, _result: &SimulationRun) {}
+
+    /// Called when the successful run numbered `run` is the cheapest one
+    /// found so far.
+    fn on_new_best(&self, _run: u32, _result: &SimulationRun) {}
+
+    /// Called when a simulation finishes with its `report`.
+    fn on_simulation_finished(&self, _report: &SimulationReport) {}
+
+    /// Called when planning a craft of `target_state` starts.
+    fn on_plan_started(&self, _target_state: &ItemState) {}
+
+    /// Called when planning a craft finishes with its `plan`.
+    fn on_plan_finished(&self, _plan: &CraftPlan) {}
+
+    /// Called when solving for `target_state` within `budget` starts.
+    fn on_budget_started(&self, _target_state: &ItemState, _budget: f32) {}
+
+    /// Called when solving within a budget finishes with its `report`.
+    fn on_budget_finished(&self, _report: &BudgetReport) {}
+
+    /// Called when recommending the next action for `item_state` starts.
+    fn on_recommend_started(&self, _item_state: &ItemState) {}
+
+    /// Called when recommending finishes with its `recommendations`.
+    fn on_recommend_finished(&self, _recommendations: &[ActionRecommendation]) {}
+
+    /// Called when a crafting action by `action_id` is applied to an item,
+    /// which is now `item_state`.
+    fn on_action_applied(&self, _action_id: &str, _item_state: &ItemState) {}
+}
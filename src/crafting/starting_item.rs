@@ -0,0 +1,60 @@
+use crate::{
+    datasets::items::Items,
+    files::versioned::Versioned,
+    items::{item_state::ItemState, rarity::Rarity},
+};
+use serde_derive::{Deserialize, Serialize};
+
+/// An item that crafting can start from, bought for a `price` in the base
+/// currency, e.g.: a magic base with a high tier modifier already rolled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StartingItem {
+    pub item: ItemState,
+    pub price: f32,
+}
+
+impl StartingItem {
+    /// Makes a new [`StartingItem`].
+    pub fn new(item: ItemState, price: f32) -> Self {
+        Self { item, price }
+    }
+
+    /// Makes a free, normal [`StartingItem`] of the same base as `target_state`.
+    pub fn from_base(items: &Items, target_state: &ItemState) -> Self {
+        Self::new(
+            ItemState::new(
+                &target_state.base,
+                &target_state.class,
+                items.get_affix_slots(&target_state.base, Rarity::Normal),
+                target_state.item_level,
+                vec![],
+                vec![],
+            ),
+            0.0,
+        )
+    }
+
+    /// Predicate that determines if crafting `target_state` can start from
+    /// this item.
+    pub fn is_candidate_for(&self, target_state: &ItemState) -> bool {
+        self.item.base == target_state.base
+            && self.item.class == target_state.class
+            && self.item.item_level >= target_state.item_level
+    }
+
+    /// Gets a short description of the item, e.g.: "magic Warlord Cuirass
+    /// [max-life]".
+    pub fn describe(&self) -> String {
+        let modifiers = self
+            .item
+            .prefixes
+            .iter()
+            .chain(self.item.suffixes.iter())
+            .map(|m| m.id.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ");
+        format!("{} {} [{modifiers}]", self.item.rarity, self.item.base)
+    }
+}
+
+impl Versioned for StartingItem {}
@@ -0,0 +1,41 @@
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    thread,
+};
+
+/// Runs `jobs` numbered jobs on a fixed pool of worker threads, sized to the
+/// available CPUs. Each worker folds the jobs it takes into its own aggregate,
+/// made by `new_aggregate`, so no state is shared between jobs, and every
+/// worker's aggregate is returned to be merged.
+pub fn run_pooled<A, N, J>(jobs: u32, new_aggregate: N, job: J) -> Vec<A>
+where
+    A: Send,
+    N: Fn() -> A + Sync,
+    J: Fn(&mut A, u32) + Sync,
+{
+    let next_job = AtomicU32::new(0);
+    let workers = thread::available_parallelism()
+        .map_or(1, |p| p.get())
+        .min(jobs.max(1) as usize);
+
+    thread::scope(|s| {
+        (0..workers)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut aggregate = new_aggregate();
+                    loop {
+                        let job_number = next_job.fetch_add(1, Ordering::Relaxed);
+                        if job_number >= jobs {
+                            break;
+                        }
+                        job(&mut aggregate, job_number);
+                    }
+                    aggregate
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
@@ -1,7 +1,7 @@
-use serde_derive::Deserialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// Represents a single affix.
-#[derive(Default, Deserialize, Clone, PartialEq)]
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Affix {
     pub name: String,
     pub id: String,
@@ -1,7 +1,7 @@
-use serde_derive::Deserialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// Represents an affix with ranges of values.
-#[derive(Default, Deserialize, PartialEq)]
+#[derive(Default, Deserialize, Serialize, PartialEq)]
 pub struct AffixRange {
     pub affix: String,
     pub range: Option<[u8; 2]>,
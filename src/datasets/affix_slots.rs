@@ -0,0 +1,40 @@
+use crate::items::{affix_side::AffixSide, rarity::Rarity};
+use serde_derive::{Deserialize, Serialize};
+
+/// Represents the most prefixes and suffixes an item can have at a rarity.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct AffixSlots {
+    pub rarity: Rarity,
+    pub prefixes: u8,
+    pub suffixes: u8,
+}
+
+impl AffixSlots {
+    /// Gets the slots used by most items at `rarity`, when a class or base
+    /// doesn't list its own.
+    pub fn default_for(rarity: Rarity) -> Self {
+        let (prefixes, suffixes) = match rarity {
+            Rarity::Normal => (0, 0),
+            Rarity::Magic => (1, 1),
+            Rarity::Rare => (3, 3),
+        };
+        Self {
+            rarity,
+            prefixes,
+            suffixes,
+        }
+    }
+
+    /// Gets the most affixes of `affix_side`.
+    pub fn get_max(&self, affix_side: AffixSide) -> u8 {
+        match affix_side {
+            AffixSide::Prefix => self.prefixes,
+            AffixSide::Suffix => self.suffixes,
+        }
+    }
+
+    /// Determines if `prefix_count` prefixes and `suffix_count` suffixes fit.
+    pub fn fits(&self, prefix_count: usize, suffix_count: usize) -> bool {
+        prefix_count <= self.prefixes.into() && suffix_count <= self.suffixes.into()
+    }
+}
@@ -1,8 +1,9 @@
 use crate::datasets::modifier_tier::ModifierTier;
-use serde_derive::Deserialize;
+use serde_derive::{Deserialize, Serialize};
 
-/// Represents a list of tiers for an affix with ranges of values.
-#[derive(Default, Deserialize, Clone, PartialEq)]
+/// Represents a list of tiers for an affix with ranges of values. Tiers are
+/// numbered from `1`, the best tier, as in the game, where `0` is no tier.
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
 pub struct AffixTier {
     pub affix: String,
     pub tiers: Vec<ModifierTier>,
@@ -14,31 +15,27 @@ impl AffixTier {
         self.tiers.iter().map(|t| t.weight).sum()
     }
 
-    /// Gets the highest possible tier for an item level.
+    /// Gets the highest possible tier for an item level, defaults to `0`.
     pub fn get_highest_tier_for_item_level(&self, item_level: u8) -> u8 {
         self.tiers
             .iter()
-            .map(|t| t.item_level)
-            .find(|ilvl| ilvl <= &item_level)
-            .unwrap_or_default()
+            .position(|t| t.item_level <= item_level)
+            .map_or(0, |index| index as u8 + 1)
+    }
+
+    /// Gets the modifier tier of a `tier` number.
+    fn get_tier(&self, tier: u8) -> Option<&ModifierTier> {
+        self.tiers.get(tier.checked_sub(1)? as usize)
     }
 
     /// Gets a specific tier's weight for an affix.
     pub fn get_tier_weight(&self, tier: u8) -> Option<u16> {
-        if tier >= self.tiers.len() as u8 {
-            None
-        } else {
-            Some(self.tiers[tier as usize].weight)
-        }
+        self.get_tier(tier).map(|t| t.weight)
     }
 
     /// Gets the minimum value for an affix modifier by `tier`.
     pub fn get_minimum_tier_value(&self, tier: u8) -> Option<u16> {
-        if tier >= self.tiers.len() as u8 {
-            None
-        } else {
-            Some(self.tiers[tier as usize].get_minimum_value())
-        }
+        self.get_tier(tier).map(|t| t.get_minimum_value())
     }
 
     /// Gets the tier of an affix modifier by `value`.
@@ -46,6 +43,6 @@ impl AffixTier {
         self.tiers
             .iter()
             .position(|t| t.get_minimum_value() <= value)
-            .map(|tier| tier as u8)
+            .map(|index| index as u8 + 1)
     }
 }
@@ -1,8 +1,13 @@
-use serde_derive::Deserialize;
+use crate::datasets::affix_slots::AffixSlots;
+use serde_derive::{Deserialize, Serialize};
 
 /// Represents an item class.
-#[derive(Default, Deserialize, PartialEq)]
+#[derive(Default, Deserialize, Serialize, PartialEq)]
 pub struct Class {
     pub name: String,
     pub id: String,
+    /// The id of the broader class this class specializes, e.g.: "armour".
+    pub parent: Option<String>,
+    /// The affix slots of items of the class at each rarity.
+    pub slots: Option<Vec<AffixSlots>>,
 }
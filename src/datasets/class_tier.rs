@@ -1,12 +1,14 @@
-use crate::datasets::affix_tier::AffixTier;
-use serde_derive::Deserialize;
+use crate::{datasets::affix_tier::AffixTier, items::affix_side::AffixSide};
+use serde_derive::{Deserialize, Serialize};
 
 /// Represents a list of tiered modifiers for an item class.
-#[derive(Default, Deserialize, PartialEq)]
+#[derive(Default, Deserialize, Serialize, PartialEq)]
 pub struct ClassTier {
     pub classes: Vec<String>,
     pub prefixes: Vec<AffixTier>,
     pub suffixes: Vec<AffixTier>,
+    pub abyssal_prefixes: Option<Vec<AffixTier>>,
+    pub abyssal_suffixes: Option<Vec<AffixTier>>,
 }
 
 impl ClassTier {
@@ -25,6 +27,46 @@ impl ClassTier {
         self.get_prefixes_weight() + self.get_suffixes_weight()
     }
 
+    /// Gets all prefixes or suffixes.
+    pub fn get_affixes(&self, affix_side: AffixSide) -> &[AffixTier] {
+        match affix_side {
+            AffixSide::Prefix => &self.prefixes,
+            AffixSide::Suffix => &self.suffixes,
+        }
+    }
+
+    /// Gets all abyssal prefixes or suffixes, defaults to an empty list.
+    pub fn get_abyssal_affixes(&self, affix_side: AffixSide) -> &[AffixTier] {
+        match affix_side {
+            AffixSide::Prefix => self.abyssal_prefixes.as_deref().unwrap_or_default(),
+            AffixSide::Suffix => self.abyssal_suffixes.as_deref().unwrap_or_default(),
+        }
+    }
+
+    /// Gets every affix tier list, including abyssal prefixes and suffixes.
+    pub fn get_all_affixes(&self) -> Vec<AffixTier> {
+        [
+            self.prefixes.as_slice(),
+            self.suffixes.as_slice(),
+            self.get_abyssal_affixes(AffixSide::Prefix),
+            self.get_abyssal_affixes(AffixSide::Suffix),
+        ]
+        .concat()
+    }
+
+    /// Gets the tiers of an `affix` on either side, including abyssal ones.
+    pub fn get_affix(&self, affix: &str) -> Option<&AffixTier> {
+        [
+            self.prefixes.as_slice(),
+            self.suffixes.as_slice(),
+            self.get_abyssal_affixes(AffixSide::Prefix),
+            self.get_abyssal_affixes(AffixSide::Suffix),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|a| a.affix.eq(affix))
+    }
+
     /// Gets the weight of all affixes for a modifier tier list.
     pub fn get_total_weight_for_item_level(&self, item_level: u8) -> u32 {
         self.prefixes
@@ -1,10 +1,140 @@
-use crate::{datasets::class_tier::ClassTier, files::from_file::FromFile};
-use serde_derive::Deserialize;
+use crate::{
+    datasets::{affix_tier::AffixTier, class_tier::ClassTier, items::Items},
+    files::from_file::FromFile,
+};
+use serde_derive::{Deserialize, Serialize};
 
 /// Represents all class-based tiered modifiers.
-#[derive(Default, Deserialize, PartialEq)]
+#[derive(Default, Deserialize, Serialize, PartialEq)]
 pub struct ClassTiers {
     pub class_tiers: Vec<ClassTier>,
 }
 
 impl FromFile for ClassTiers {}
+
+impl ClassTiers {
+    /// Resolves the tiers through the class hierarchy of `items`, giving each
+    /// class its own tier list with the affixes of every class it
+    /// specializes, where the more specific class overrides shared affixes.
+    pub fn resolve(&self, items: &Items) -> ClassTiers {
+        let mut classes: Vec<&str> = vec![];
+        let known_classes = self
+            .class_tiers
+            .iter()
+            .flat_map(|ct| ct.classes.iter())
+            .chain(items.classes.iter().map(|c| &c.id))
+            .chain(items.items.iter().map(|i| &i.class));
+        for class in known_classes {
+            if !classes.contains(&class.as_str()) {
+                classes.push(class);
+            }
+        }
+
+        let class_tiers = classes
+            .into_iter()
+            .filter_map(|class| self.resolve_class(items, class))
+            .collect();
+        ClassTiers { class_tiers }
+    }
+
+    /// Merges the tier lists of `class` and the classes it specializes,
+    /// defaults to `None` if none of them have tiers.
+    fn resolve_class(&self, items: &Items, class: &str) -> Option<ClassTier> {
+        let merge = |tiers: &mut Vec<AffixTier>, affixes: &[AffixTier]| {
+            for affix in affixes {
+                if !tiers.iter().any(|t| t.affix.eq(&affix.affix)) {
+                    tiers.push(affix.clone());
+                }
+            }
+        };
+
+        let mut resolved = ClassTier {
+            classes: vec![class.to_string()],
+            ..Default::default()
+        };
+        let (mut abyssal_prefixes, mut abyssal_suffixes) = (vec![], vec![]);
+        let mut found = false;
+        // most specific first, so that broader classes can't override affixes
+        for lineage_class in items.get_class_lineage(class) {
+            let Some(class_tier) = self
+                .class_tiers
+                .iter()
+                .find(|ct| ct.classes.iter().any(|c| c.eq(lineage_class)))
+            else {
+                continue;
+            };
+            found = true;
+            merge(&mut resolved.prefixes, &class_tier.prefixes);
+            merge(&mut resolved.suffixes, &class_tier.suffixes);
+            merge(
+                &mut abyssal_prefixes,
+                class_tier.abyssal_prefixes.as_deref().unwrap_or_default(),
+            );
+            merge(
+                &mut abyssal_suffixes,
+                class_tier.abyssal_suffixes.as_deref().unwrap_or_default(),
+            );
+        }
+
+        resolved.abyssal_prefixes = Some(abyssal_prefixes).filter(|a| !a.is_empty());
+        resolved.abyssal_suffixes = Some(abyssal_suffixes).filter(|a| !a.is_empty());
+        found.then_some(resolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve_class(class: &str) -> ClassTier {
+        let items = Items::default().populate("assets/items.toml");
+        ClassTiers::default()
+            .populate("assets/class_tiers.toml")
+            .resolve(&items)
+            .class_tiers
+            .into_iter()
+            .find(|ct| ct.classes.iter().any(|c| c.eq(class)))
+            .expect("resolved class tiers")
+    }
+
+    fn get_affix<'a>(affixes: &'a [AffixTier], affix: &str) -> Option<&'a AffixTier> {
+        affixes.iter().find(|a| a.affix.eq(affix))
+    }
+
+    #[test]
+    fn specialized_class_overrides_shared_affixes() {
+        let str_chest = resolve_class("str-chest");
+        let chest = resolve_class("chest");
+
+        let str_chest_armor = get_affix(&str_chest.prefixes, "armor").expect("armor");
+        let chest_armor = get_affix(&chest.prefixes, "armor").expect("armor");
+        assert_eq!(str_chest_armor.tiers.len(), chest_armor.tiers.len() + 1);
+        assert_eq!(
+            str_chest
+                .prefixes
+                .iter()
+                .filter(|a| a.affix.eq("armor"))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn specialized_class_inherits_every_ancestor() {
+        let str_chest = resolve_class("str-chest");
+
+        // from "chest", then "armour"
+        assert!(get_affix(&str_chest.prefixes, "phys-thorns-dmg").is_some());
+        assert!(get_affix(&str_chest.prefixes, "max-life").is_some());
+        assert!(get_affix(&str_chest.suffixes, "fire-res").is_some());
+    }
+
+    #[test]
+    fn classes_without_a_parent_keep_their_own_tiers() {
+        let jewel = resolve_class("jewel");
+
+        assert_eq!(jewel.prefixes.len(), 2);
+        assert_eq!(jewel.suffixes.len(), 2);
+        assert!(get_affix(&jewel.prefixes, "max-life").is_none());
+    }
+}
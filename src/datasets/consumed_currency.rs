@@ -0,0 +1,9 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// Represents an amount of a currency consumed by a crafting action.
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ConsumedCurrency {
+    pub currency: String,
+    pub amount: u16,
+}
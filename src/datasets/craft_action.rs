@@ -1,84 +1,131 @@
-use crate::datasets::craft_outcome::CraftOutcome;
-use serde_derive::Deserialize;
+use crate::{
+    datasets::{
+        consumed_currency::ConsumedCurrency, craft_outcome::CraftOutcome,
+        outcome_action::OutcomeAction, outcome_affix::OutcomeAffix, prices::Prices,
+    },
+    items::rarity::Rarity,
+};
+use serde_derive::{Deserialize, Serialize};
 
 /// Represents a crafting action.
-#[derive(Default, Deserialize, Clone, PartialEq)]
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
 pub struct CraftAction {
     pub name: String,
     pub id: String,
-    pub cost: f32,
+    pub consumes: Vec<ConsumedCurrency>,
     pub currency: Option<String>,
-    pub rarity: Option<String>,
+    pub rarity: Option<Rarity>,
+    /// The rarity the item is upgraded to before any affixes are added.
+    pub upgrades_to: Option<Rarity>,
     pub outcomes: Vec<CraftOutcome>,
 }
 
 impl CraftAction {
+    /// Gets the cost of the action in the base currency of `prices`, from
+    /// every currency it consumes. Consumed currencies are checked to have a
+    /// price when the datasets are loaded.
+    pub fn get_cost(&self, prices: &Prices) -> f32 {
+        self.consumes
+            .iter()
+            .map(|cc| {
+                let price = prices
+                    .get_price_by_currency(&cc.currency)
+                    .map(|p| p.price)
+                    .unwrap_or_default();
+                price * cc.amount as f32
+            })
+            .sum()
+    }
+
     pub fn only_adds_prefix(&self) -> bool {
-        self.does_only_affix_action("prefix", "add")
+        self.does_only_affix_action(OutcomeAffix::Prefix, OutcomeAction::Add)
     }
 
     pub fn only_adds_suffix(&self) -> bool {
-        self.does_only_affix_action("suffix", "add")
+        self.does_only_affix_action(OutcomeAffix::Suffix, OutcomeAction::Add)
     }
 
     pub fn only_removes_prefix(&self) -> bool {
-        self.does_only_affix_action("prefix", "remove")
+        self.does_only_affix_action(OutcomeAffix::Prefix, OutcomeAction::Remove)
     }
 
     pub fn only_removes_suffix(&self) -> bool {
-        self.does_only_affix_action("suffix", "remove")
+        self.does_only_affix_action(OutcomeAffix::Suffix, OutcomeAction::Remove)
     }
 
     pub fn only_targets_prefix(&self) -> bool {
-        self.does_only_affix_action("prefix", "target")
+        self.does_only_affix_action(OutcomeAffix::Prefix, OutcomeAction::Target)
     }
 
     pub fn only_targets_suffix(&self) -> bool {
-        self.does_only_affix_action("suffix", "target")
+        self.does_only_affix_action(OutcomeAffix::Suffix, OutcomeAction::Target)
     }
 
     pub fn adds_prefix(&self) -> bool {
-        self.does_affix_action("prefix", "add")
+        self.does_affix_action(OutcomeAffix::Prefix, OutcomeAction::Add)
     }
 
     pub fn adds_suffix(&self) -> bool {
-        self.does_affix_action("suffix", "add")
+        self.does_affix_action(OutcomeAffix::Suffix, OutcomeAction::Add)
     }
 
     pub fn removes_prefix(&self) -> bool {
-        self.does_affix_action("prefix", "remove")
+        self.does_affix_action(OutcomeAffix::Prefix, OutcomeAction::Remove)
     }
 
     pub fn removes_suffix(&self) -> bool {
-        self.does_affix_action("suffix", "remove")
+        self.does_affix_action(OutcomeAffix::Suffix, OutcomeAction::Remove)
     }
 
     pub fn targets_prefix(&self) -> bool {
-        self.does_affix_action("prefix", "target")
+        self.does_affix_action(OutcomeAffix::Prefix, OutcomeAction::Target)
     }
 
     pub fn targets_suffix(&self) -> bool {
-        self.does_affix_action("suffix", "target")
+        self.does_affix_action(OutcomeAffix::Suffix, OutcomeAction::Target)
     }
 
     pub fn targets_lowest_tier(&self) -> bool {
-        self.does_only_affix_action("lowest", "target")
+        self.does_only_affix_action(OutcomeAffix::Lowest, OutcomeAction::Target)
+    }
+
+    pub fn targets_desecrated(&self) -> bool {
+        self.does_only_affix_action(OutcomeAffix::Desecrated, OutcomeAction::Target)
+    }
+
+    pub fn desecrates_affix(&self) -> bool {
+        self.outcomes
+            .iter()
+            .all(|o| o.action == OutcomeAction::Desecrate)
+    }
+
+    pub fn reveals_affix(&self) -> bool {
+        self.outcomes
+            .iter()
+            .all(|o| o.action == OutcomeAction::Reveal)
     }
 
     pub fn targets_affix(&self) -> bool {
-        self.outcomes.iter().all(|o| o.action.eq("target"))
+        self.outcomes
+            .iter()
+            .all(|o| o.action == OutcomeAction::Target)
     }
 
     pub fn adds_affix(&self) -> bool {
-        self.outcomes.iter().all(|o| o.action.eq("add"))
+        self.outcomes.iter().all(|o| o.action == OutcomeAction::Add)
     }
 
     pub fn removes_affix(&self) -> bool {
-        self.outcomes.iter().all(|o| o.action.eq("remove"))
+        self.outcomes
+            .iter()
+            .all(|o| o.action == OutcomeAction::Remove)
     }
 
     pub fn replaces_affix(&self) -> bool {
-        self.outcomes.iter().all(|o| o.action.eq("replace"))
+        self.outcomes
+            .iter()
+            .all(|o| o.action == OutcomeAction::Replace)
     }
 
     pub fn expects_chaos(&self) -> bool {
@@ -89,15 +136,30 @@ impl CraftAction {
         self.targets_affix() && self.currency.clone().unwrap_or_default().eq("annul")
     }
 
-    fn does_affix_action(&self, affix: &str, action: &str) -> bool {
+    pub fn expects_reveal(&self) -> bool {
+        self.targets_affix() && self.currency.clone().unwrap_or_default().eq("reveal")
+    }
+
+    /// Determines if any outcome does one of `actions` to one of `affixes`.
+    pub fn does_any_affix_action(
+        &self,
+        actions: &[OutcomeAction],
+        affixes: &[OutcomeAffix],
+    ) -> bool {
+        self.outcomes
+            .iter()
+            .any(|o| actions.contains(&o.action) && affixes.contains(&o.affix))
+    }
+
+    fn does_affix_action(&self, affix: OutcomeAffix, action: OutcomeAction) -> bool {
         self.outcomes
             .iter()
-            .any(|o| o.action.eq(action) && o.affix.eq(affix))
+            .any(|o| o.action == action && o.affix == affix)
     }
 
-    fn does_only_affix_action(&self, affix: &str, action: &str) -> bool {
+    fn does_only_affix_action(&self, affix: OutcomeAffix, action: OutcomeAction) -> bool {
         self.outcomes
             .iter()
-            .all(|o| o.action.eq(action) && o.affix.eq(affix))
+            .all(|o| o.action == action && o.affix == affix)
     }
 }
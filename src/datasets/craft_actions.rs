@@ -1,8 +1,14 @@
-use crate::{datasets::craft_action::CraftAction, files::from_file::FromFile};
-use serde_derive::Deserialize;
+use crate::{
+    datasets::{
+        craft_action::CraftAction, outcome_action::OutcomeAction, outcome_affix::OutcomeAffix,
+        prices::Prices,
+    },
+    files::from_file::FromFile,
+};
+use serde_derive::{Deserialize, Serialize};
 
 /// Represents all crafting actions.
-#[derive(Default, Deserialize, Clone, PartialEq)]
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
 pub struct CraftActions {
     pub craft_actions: Vec<CraftAction>,
 }
@@ -10,24 +16,82 @@ pub struct CraftActions {
 impl CraftActions {
     /// Gets a crafting action by `id`.
     pub fn get_action_by_id(&self, id: &str) -> Option<CraftAction> {
-        self.craft_actions
-            .clone()
-            .into_iter()
-            .find(|ca| ca.id.eq(id))
+        self.get_action(id).cloned()
+    }
+
+    /// Gets a reference to a crafting action by `id`, without cloning it.
+    pub fn get_action(&self, id: &str) -> Option<&CraftAction> {
+        self.craft_actions.iter().find(|ca| ca.id.eq(id))
+    }
+
+    /// Gets every currency consumed by an action that has no price in
+    /// `prices`, without duplicates.
+    pub fn get_unpriced_currencies(&self, prices: &Prices) -> Vec<&str> {
+        let mut currencies: Vec<&str> = vec![];
+        for consumed in self.craft_actions.iter().flat_map(|ca| &ca.consumes) {
+            if prices.get_price_by_currency(&consumed.currency).is_none()
+                && !currencies.contains(&consumed.currency.as_str())
+            {
+                currencies.push(&consumed.currency);
+            }
+        }
+        currencies
     }
 
     /// Gets all crafting actions except ones that `action` any `affixes`.
-    pub fn get_actions_except(&self, actions: &[String], affixes: &[String]) -> Vec<CraftAction> {
+    pub fn get_actions_except(
+        &self,
+        actions: &[OutcomeAction],
+        affixes: &[OutcomeAffix],
+    ) -> Vec<&CraftAction> {
         self.craft_actions
-            .clone()
-            .into_iter()
-            .filter(|ca| {
-                !ca.outcomes
-                    .iter()
-                    .any(|o| actions.contains(&o.action) && affixes.contains(&o.affix))
-            })
+            .iter()
+            .filter(|ca| !ca.does_any_affix_action(actions, affixes))
             .collect()
     }
 }
 
 impl FromFile for CraftActions {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGAL_ORB: &str = r#"craft_actions = [
+        { name = "regal orb", id = "regal", consumes = [{ currency = "regal", amount = 1 }], rarity = "magic", upgrades_to = "rare", outcomes = [{ action = "add", affix = "random", probability = 1.0 }]},
+    ]"#;
+
+    fn parse(text: &str) -> Result<CraftActions, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    #[test]
+    fn loads_every_crafting_action() {
+        let craft_actions = CraftActions::default().populate("assets/craft_actions.toml");
+
+        assert!(!craft_actions.craft_actions.is_empty());
+        assert!(parse(REGAL_ORB).is_ok());
+    }
+
+    #[test]
+    fn rejects_unknown_values() {
+        for (value, typo) in [
+            (r#"action = "add""#, r#"action = "ad""#),
+            (r#"affix = "random""#, r#"affix = "randm""#),
+            (r#"upgrades_to = "rare""#, r#"upgrades_to = "rar""#),
+        ] {
+            assert!(parse(&REGAL_ORB.replace(value, typo)).is_err(), "{typo}");
+        }
+    }
+
+    #[test]
+    fn rejects_misspelled_keys() {
+        for (key, typo) in [
+            ("upgrades_to =", "upgrade_to ="),
+            ("probability =", "probabilty ="),
+            ("amount =", "amuont ="),
+        ] {
+            assert!(parse(&REGAL_ORB.replace(key, typo)).is_err(), "{typo}");
+        }
+    }
+}
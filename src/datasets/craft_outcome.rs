@@ -1,10 +1,12 @@
-use serde_derive::Deserialize;
+use crate::datasets::{outcome_action::OutcomeAction, outcome_affix::OutcomeAffix};
+use serde_derive::{Deserialize, Serialize};
 
 /// Represents a potential crafting outcome.
-#[derive(Default, Deserialize, Clone, PartialEq)]
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
 pub struct CraftOutcome {
-    pub action: String,
-    pub affix: String,
+    pub action: OutcomeAction,
+    pub affix: OutcomeAffix,
     pub count: Option<u8>,
     pub probability: f32,
 }
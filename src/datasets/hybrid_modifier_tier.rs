@@ -1,8 +1,8 @@
 use rand::Rng;
-use serde_derive::Deserialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// Represents a list of tiers for a hybrid modifier.
-#[derive(Default, Deserialize, Clone, PartialEq)]
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
 pub struct HybridModifierTier {
     pub affix: String,
     pub range: Option<[u16; 2]>,
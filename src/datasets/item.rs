@@ -1,13 +1,15 @@
-use crate::datasets::affix_range::AffixRange;
 use crate::datasets::stat::Stat;
-use serde_derive::Deserialize;
+use crate::datasets::{affix_range::AffixRange, affix_slots::AffixSlots};
+use serde_derive::{Deserialize, Serialize};
 
 /// Represents an item.
-#[derive(Default, Deserialize, PartialEq)]
+#[derive(Default, Deserialize, Serialize, PartialEq)]
 pub struct Item {
     pub name: String,
     pub class: String,
     pub stats: Option<Vec<Stat>>,
     pub character_level: Option<u8>,
     pub implicits: Option<Vec<AffixRange>>,
+    /// The affix slots of the base at each rarity, instead of its class slots.
+    pub slots: Option<Vec<AffixSlots>>,
 }
@@ -1,14 +1,146 @@
 use crate::{
-    datasets::{class::Class, item::Item},
+    datasets::{affix_slots::AffixSlots, class::Class, item::Item},
     files::from_file::FromFile,
+    items::rarity::Rarity,
 };
-use serde_derive::Deserialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// Represents a collection of items and their related item class.
-#[derive(Default, Deserialize, PartialEq)]
+#[derive(Default, Deserialize, Serialize, PartialEq)]
 pub struct Items {
     pub classes: Vec<Class>,
     pub items: Vec<Item>,
 }
 
+impl Items {
+    /// Gets the affix slots of a `base` at `rarity`, from the base itself,
+    /// then its classes from most to least specific, defaulting to the slots
+    /// of most items.
+    pub fn get_affix_slots(&self, base: &str, rarity: Rarity) -> AffixSlots {
+        let find_slots = |slots: &Option<Vec<AffixSlots>>| {
+            slots
+                .as_ref()
+                .and_then(|s| s.iter().find(|s| s.rarity == rarity).copied())
+        };
+        let item = self.items.iter().find(|i| i.name.eq(base));
+        item.and_then(|i| find_slots(&i.slots))
+            .or_else(|| {
+                let class = item.map(|i| i.class.as_str()).unwrap_or_default();
+                self.get_class_lineage(class).into_iter().find_map(|c| {
+                    self.classes
+                        .iter()
+                        .find(|ic| ic.id.eq(c))
+                        .and_then(|ic| find_slots(&ic.slots))
+                })
+            })
+            .unwrap_or_else(|| AffixSlots::default_for(rarity))
+    }
+
+    /// Gets `class` followed by every class it specializes, from most to
+    /// least specific, e.g.: ["ring", "jewellery"].
+    pub fn get_class_lineage<'a>(&'a self, class: &'a str) -> Vec<&'a str> {
+        let mut lineage = vec![class];
+        while let Some(parent) = self
+            .classes
+            .iter()
+            .find(|c| c.id.eq(lineage[lineage.len() - 1]))
+            .and_then(|c| c.parent.as_deref())
+        {
+            // a class can't specialize itself, even through other classes
+            if lineage.contains(&parent) {
+                break;
+            }
+            lineage.push(parent);
+        }
+        lineage
+    }
+
+    /// Finds the entry of `entries` for the most specific class in the
+    /// lineage of `class`, where `get_classes` gets the classes of an entry.
+    pub fn find_for_class<'a, T>(
+        &self,
+        class: &str,
+        entries: &'a [T],
+        get_classes: impl Fn(&T) -> &[String],
+    ) -> Option<&'a T> {
+        self.get_class_lineage(class).into_iter().find_map(|c| {
+            entries
+                .iter()
+                .find(|e| get_classes(e).iter().any(|ec| ec.eq(c)))
+        })
+    }
+}
+
 impl FromFile for Items {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> Items {
+        Items::default().populate("assets/items.toml")
+    }
+
+    #[test]
+    fn classes_inherit_slots_from_their_parent() {
+        let slots = items().get_affix_slots("Warlord Cuirass", Rarity::Rare);
+
+        assert_eq!((slots.prefixes, slots.suffixes), (3, 3));
+    }
+
+    #[test]
+    fn classes_inherit_slots_through_every_ancestor() {
+        let slots = items().get_affix_slots("Conqueror Plate", Rarity::Rare);
+
+        assert_eq!((slots.prefixes, slots.suffixes), (3, 3));
+    }
+
+    #[test]
+    fn class_lineage_goes_from_most_to_least_specific() {
+        assert_eq!(
+            items().get_class_lineage("str-chest"),
+            vec!["str-chest", "chest", "armour"]
+        );
+    }
+
+    #[test]
+    fn class_lineage_stops_at_a_cycle() {
+        let items: Items = toml::from_str(
+            r#"
+            classes = [
+                { name = "A", id = "a", parent = "b" },
+                { name = "B", id = "b", parent = "c" },
+                { name = "C", id = "c", parent = "a" },
+            ]
+            items = []
+            "#,
+        )
+        .expect("valid items");
+
+        assert_eq!(items.get_class_lineage("a"), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn jewels_have_fewer_rare_slots() {
+        let items = items();
+        let magic_slots = items.get_affix_slots("Emerald", Rarity::Magic);
+        let rare_slots = items.get_affix_slots("Emerald", Rarity::Rare);
+
+        assert_eq!((magic_slots.prefixes, magic_slots.suffixes), (1, 1));
+        assert_eq!((rare_slots.prefixes, rare_slots.suffixes), (2, 2));
+    }
+
+    #[test]
+    fn base_slots_override_class_slots() {
+        let items: Items = toml::from_str(
+            r#"
+            classes = [{ name = "Jewel", id = "jewel", slots = [{ rarity = "rare", prefixes = 2, suffixes = 2 }] }]
+            items = [{ name = "Time-Lost Emerald", class = "jewel", slots = [{ rarity = "rare", prefixes = 1, suffixes = 1 }] }]
+            "#,
+        )
+        .expect("valid items");
+        let slots = items.get_affix_slots("Time-Lost Emerald", Rarity::Rare);
+
+        assert_eq!((slots.prefixes, slots.suffixes), (1, 1));
+    }
+}
@@ -0,0 +1,9 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// Represents the chance of keeping an amount of modifiers when recombining.
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ModifierCount {
+    pub total: u8,
+    pub kept: u8,
+    pub probability: f32,
+}
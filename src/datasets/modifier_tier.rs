@@ -1,9 +1,9 @@
 use crate::datasets::hybrid_modifier_tier::HybridModifierTier;
 use rand::Rng;
-use serde_derive::Deserialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// Represents a list of tiers for a modifier.
-#[derive(Default, Deserialize, Clone, PartialEq)]
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
 pub struct ModifierTier {
     pub range: Option<[u16; 2]>,
     pub min: Option<[u16; 2]>,
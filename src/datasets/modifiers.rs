@@ -1,8 +1,8 @@
 use crate::{datasets::affix::Affix, files::from_file::FromFile};
-use serde_derive::Deserialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// Represents all modifiers.
-#[derive(Default, Deserialize, Clone, PartialEq)]
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Modifiers {
     pub modifiers: Vec<Affix>,
 }
@@ -0,0 +1,28 @@
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+/// Represents what a crafting outcome does to an item.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutcomeAction {
+    #[default]
+    Add,
+    Remove,
+    Replace,
+    Target,
+    Desecrate,
+    Reveal,
+}
+
+impl fmt::Display for OutcomeAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Add => "add",
+            Self::Remove => "remove",
+            Self::Replace => "replace",
+            Self::Target => "target",
+            Self::Desecrate => "desecrate",
+            Self::Reveal => "reveal",
+        })
+    }
+}
@@ -0,0 +1,37 @@
+use crate::items::affix_side::AffixSide;
+use serde_derive::{Deserialize, Serialize};
+
+/// Represents which affixes a crafting outcome applies to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutcomeAffix {
+    Prefix,
+    Suffix,
+    /// Either a prefix or a suffix, chosen when the outcome is applied.
+    #[default]
+    Random,
+    /// The affix with the lowest tier.
+    Lowest,
+    /// The desecrated affix.
+    Desecrated,
+}
+
+impl OutcomeAffix {
+    /// Gets the affix side that the outcome only applies to, if there is one.
+    pub fn get_side(&self) -> Option<AffixSide> {
+        match self {
+            Self::Prefix => Some(AffixSide::Prefix),
+            Self::Suffix => Some(AffixSide::Suffix),
+            _ => None,
+        }
+    }
+}
+
+impl From<AffixSide> for OutcomeAffix {
+    fn from(affix_side: AffixSide) -> Self {
+        match affix_side {
+            AffixSide::Prefix => Self::Prefix,
+            AffixSide::Suffix => Self::Suffix,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use serde_derive::{Deserialize, Serialize};
+use toml::value::Datetime;
+
+/// Represents the price of a currency in the base currency.
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Price {
+    pub currency: String,
+    pub name: String,
+    /// The name of more than one of the currency, defaults to `name`.
+    pub plural: Option<String>,
+    pub price: f32,
+    pub updated: Option<Datetime>,
+}
+
+impl Price {
+    /// Gets the name of an `amount` of the currency, e.g.: "divine orbs".
+    pub fn get_name(&self, amount: f32) -> &str {
+        match &self.plural {
+            Some(plural) if amount != 1.0 => plural,
+            _ => &self.name,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{datasets::price::Price, files::from_file::FromFile};
+use serde_derive::{Deserialize, Serialize};
+
+/// Represents the prices of all currencies in a base currency.
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Prices {
+    pub base_currency: String,
+    pub prices: Vec<Price>,
+}
+
+impl Prices {
+    /// Gets the price of a `currency` by id.
+    pub fn get_price_by_currency(&self, currency: &str) -> Option<&Price> {
+        self.prices.iter().find(|p| p.currency.eq(currency))
+    }
+
+    /// Converts an `amount` of the base currency into `currency`, defaults to
+    /// the `amount` if `currency` has no price.
+    pub fn convert(&self, amount: f32, currency: &str) -> f32 {
+        match self.get_price_by_currency(currency) {
+            Some(price) if price.price > 0.0 => amount / price.price,
+            _ => amount,
+        }
+    }
+
+    /// Converts an `amount` of `currency` into the base currency, defaults to
+    /// the `amount` if `currency` has no price.
+    pub fn to_base_currency(&self, amount: f32, currency: &str) -> f32 {
+        match self.get_price_by_currency(currency) {
+            Some(price) => amount * price.price,
+            None => amount,
+        }
+    }
+
+    /// Formats an `amount` of the base currency as `currency`, e.g.:
+    /// "~1.25 divine orbs".
+    pub fn format_cost(&self, amount: f32, currency: &str) -> String {
+        let currency = if self.get_price_by_currency(currency).is_some() {
+            currency
+        } else {
+            &self.base_currency
+        };
+        let amount = self.convert(amount, currency);
+        let name = self
+            .get_price_by_currency(currency)
+            .map(|p| p.get_name(amount))
+            .unwrap_or(currency);
+        format!("~{amount:.2} {name}")
+    }
+}
+
+impl FromFile for Prices {}
@@ -0,0 +1,29 @@
+use crate::datasets::{
+    modifier_count::ModifierCount, recombine_base::RecombineBase,
+    recombine_item_level::RecombineItemLevel,
+};
+use serde_derive::{Deserialize, Serialize};
+
+/// Represents the recombination rules and probability tables for a list of
+/// item classes.
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Recombination {
+    pub classes: Vec<String>,
+    pub coefficient: u32,
+    pub base: RecombineBase,
+    pub item_level: RecombineItemLevel,
+    /// The chance of keeping an amount of modifiers, only for the totals
+    /// that differ from the shared table.
+    #[serde(default)]
+    pub modifier_counts: Vec<ModifierCount>,
+}
+
+impl Recombination {
+    /// Gets every [`ModifierCount`] for a `total` amount of modifiers.
+    pub fn get_modifier_counts(&self, total: u8) -> Vec<&ModifierCount> {
+        self.modifier_counts
+            .iter()
+            .filter(|mc| mc.total == total)
+            .collect()
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{
+    datasets::{items::Items, modifier_count::ModifierCount, recombination::Recombination},
+    files::from_file::FromFile,
+};
+use serde_derive::{Deserialize, Serialize};
+
+/// Represents all class-based recombination rules.
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Recombinations {
+    /// The chance of keeping an amount of modifiers, shared by every class
+    /// that doesn't list its own chance for a total.
+    #[serde(default)]
+    pub modifier_counts: Vec<ModifierCount>,
+    pub recombinations: Vec<Recombination>,
+}
+
+impl Recombinations {
+    /// Resolves the shared modifier counts into every recombination, keeping
+    /// the counts a recombination lists for a total instead.
+    pub fn resolve(mut self) -> Recombinations {
+        for recombination in &mut self.recombinations {
+            let shared_counts = self
+                .modifier_counts
+                .iter()
+                .filter(|mc| recombination.get_modifier_counts(mc.total).is_empty())
+                .cloned()
+                .collect::<Vec<ModifierCount>>();
+            recombination.modifier_counts.extend(shared_counts);
+        }
+        self
+    }
+
+    /// Gets the recombination rules for an item `class`, or the closest
+    /// class it specializes in `items`.
+    pub fn get_recombination_for_class(
+        &self,
+        items: &Items,
+        class: &str,
+    ) -> Option<&Recombination> {
+        items.find_for_class(class, &self.recombinations, |r| &r.classes)
+    }
+}
+
+impl FromFile for Recombinations {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_coefficient(class: &str) -> Option<u32> {
+        let items = Items::default().populate("assets/items.toml");
+        Recombinations::default()
+            .populate("assets/recombination.toml")
+            .resolve()
+            .get_recombination_for_class(&items, class)
+            .map(|r| r.coefficient)
+    }
+
+    #[test]
+    fn only_chests_recombine_with_the_chest_coefficient() {
+        assert_eq!(get_coefficient("chest"), Some(5));
+        assert_eq!(get_coefficient("str-chest"), Some(5));
+        assert_eq!(get_coefficient("helmet"), Some(1));
+        assert_eq!(get_coefficient("ring"), Some(1));
+        assert_eq!(get_coefficient("jewel"), Some(1));
+    }
+
+    #[test]
+    fn recombinations_keep_their_own_counts_over_shared_ones() {
+        let recombinations: Recombinations = toml::from_str(
+            r#"
+            modifier_counts = [
+                { total = 1, kept = 1, probability = 0.5 },
+                { total = 1, kept = 0, probability = 0.5 },
+                { total = 2, kept = 1, probability = 1.0 },
+            ]
+            [[recombinations]]
+            classes = ["chest"]
+            coefficient = 5
+            base = "weight"
+            item_level = "highest"
+            modifier_counts = [{ total = 2, kept = 2, probability = 1.0 }]
+            "#,
+        )
+        .expect("valid recombinations");
+        let recombination = &recombinations.resolve().recombinations[0];
+
+        assert_eq!(recombination.get_modifier_counts(1).len(), 2);
+        let two_counts = recombination.get_modifier_counts(2);
+        assert_eq!(two_counts.len(), 1);
+        assert_eq!(two_counts[0].kept, 2);
+    }
+
+    #[test]
+    fn every_weapon_has_recombination_rules() {
+        assert_eq!(get_coefficient("one-hand-mace"), Some(1));
+        assert_eq!(get_coefficient("spear"), Some(8));
+    }
+}
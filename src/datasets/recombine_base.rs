@@ -0,0 +1,14 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// Represents which of two recombined items is kept as the base.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RecombineBase {
+    /// The item whose rarest carried modifier is most common.
+    #[default]
+    Weight,
+    /// The item with the highest item level.
+    ItemLevel,
+    /// Always the left item.
+    Left,
+}
@@ -0,0 +1,16 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// Represents how the item level of a recombined item is chosen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RecombineItemLevel {
+    /// The highest item level of both items.
+    #[default]
+    Highest,
+    /// The lowest item level of both items.
+    Lowest,
+    /// The average item level of both items, rounded down.
+    Average,
+    /// The item level of the selected base item.
+    Base,
+}
@@ -0,0 +1,31 @@
+use crate::{datasets::valued_modifier::ValuedModifier, items::item_state::ItemState};
+use serde_derive::{Deserialize, Serialize};
+
+/// Represents the resale price of items with a combination of modifiers.
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Valuation {
+    pub name: String,
+    pub classes: Option<Vec<String>>,
+    pub modifiers: Vec<ValuedModifier>,
+    pub price: f32,
+    pub currency: String,
+}
+
+impl Valuation {
+    /// Predicate that determines if `item_state` has every modifier of this
+    /// valuation, and is one of its classes if there are any.
+    pub fn applies_to(&self, item_state: &ItemState) -> bool {
+        let has_class = self
+            .classes
+            .as_ref()
+            .is_none_or(|c| c.contains(&item_state.class));
+        has_class
+            && self.modifiers.iter().all(|vm| {
+                item_state
+                    .prefixes
+                    .iter()
+                    .chain(item_state.suffixes.iter())
+                    .any(|m| vm.is_met_by(m))
+            })
+    }
+}
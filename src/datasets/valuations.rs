@@ -0,0 +1,27 @@
+use crate::{
+    datasets::{prices::Prices, valuation::Valuation},
+    files::from_file::FromFile,
+    items::item_state::ItemState,
+};
+use serde_derive::{Deserialize, Serialize};
+
+/// Represents all resale prices of items, used to credit the salvage value of
+/// items left over from failed crafts.
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Valuations {
+    pub valuations: Vec<Valuation>,
+}
+
+impl Valuations {
+    /// Gets the resale value of `item_state` in the base currency, using the
+    /// most valuable valuation that applies to it, defaults to `0.0`.
+    pub fn get_item_value(&self, item_state: &ItemState, prices: &Prices) -> f32 {
+        self.valuations
+            .iter()
+            .filter(|v| v.applies_to(item_state))
+            .map(|v| prices.to_base_currency(v.price, &v.currency))
+            .fold(0.0, f32::max)
+    }
+}
+
+impl FromFile for Valuations {}
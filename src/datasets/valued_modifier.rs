@@ -0,0 +1,20 @@
+use crate::items::modifier::Modifier;
+use serde_derive::{Deserialize, Serialize};
+
+/// Represents a modifier an item must have for a valuation to apply, with an
+/// optional worst tier and minimum value.
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ValuedModifier {
+    pub id: String,
+    pub tier: Option<u8>,
+    pub value: Option<u16>,
+}
+
+impl ValuedModifier {
+    /// Predicate that determines if `modifier` is at least this valued modifier.
+    pub fn is_met_by(&self, modifier: &Modifier) -> bool {
+        modifier.id == self.id
+            && self.tier.is_none_or(|t| (1..=t).contains(&modifier.tier))
+            && self.value.is_none_or(|v| modifier.value >= v)
+    }
+}
@@ -15,6 +15,12 @@ pub trait FromFile {
                 exit(1)
             }
         };
-        toml::from_str(&contents).unwrap()
+        match toml::from_str(&contents) {
+            Ok(populated) => populated,
+            Err(e) => {
+                log_error!("Could not parse file `{file_name}`: {e}");
+                exit(1)
+            }
+        }
     }
 }
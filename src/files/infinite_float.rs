@@ -0,0 +1,17 @@
+//! Serializes floats that may be infinite, which JSON cannot represent, as
+//! `null`, e.g.: the expected cost of an impossible target.
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// Serializes `value`, or `null` if it isn't finite.
+pub fn serialize<S: Serializer>(value: &f32, serializer: S) -> Result<S::Ok, S::Error> {
+    if value.is_finite() {
+        serializer.serialize_f32(*value)
+    } else {
+        serializer.serialize_none()
+    }
+}
+
+/// Deserializes a float, where `null` is infinite.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    Ok(Option::<f32>::deserialize(deserializer)?.unwrap_or(f32::INFINITY))
+}
@@ -0,0 +1,25 @@
+use std::fmt;
+
+/// An error while saving or loading versioned data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaError {
+    InvalidJson(String),
+    MissingVersion,
+    UnsupportedVersion(u64),
+    Io(String),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidJson(error) => write!(f, "invalid JSON: {error}"),
+            Self::MissingVersion => write!(f, "data has no \"schema_version\""),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "schema version {version} is not supported")
+            }
+            Self::Io(error) => write!(f, "could not access file: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
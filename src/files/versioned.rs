@@ -0,0 +1,163 @@
+use crate::files::schema_error::SchemaError;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_derive::{Deserialize, Serialize};
+use std::fs;
+
+/// The version of the schema that data is saved with, which increases with
+/// every breaking change to a saved type.
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// Represents saved data, along with the schema version it was saved with.
+#[derive(Serialize, Deserialize)]
+struct VersionedData<T> {
+    schema_version: u64,
+    data: T,
+}
+
+/// Responsible for saving and loading any `struct` as versioned JSON.
+pub trait Versioned: Serialize + DeserializeOwned {
+    /// Formats `self` as pretty-printed, versioned JSON.
+    fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&VersionedData {
+            schema_version: SCHEMA_VERSION,
+            data: self,
+        })
+        .expect("domain types are always valid JSON")
+    }
+
+    /// Parses versioned JSON, rejecting any other schema version.
+    fn from_json(json: &str) -> Result<Self, SchemaError> {
+        let value = serde_json::from_str::<serde_json::Value>(json)
+            .map_err(|e| SchemaError::InvalidJson(e.to_string()))?;
+        let schema_version = value
+            .get("schema_version")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or(SchemaError::MissingVersion)?;
+        if schema_version != SCHEMA_VERSION {
+            return Err(SchemaError::UnsupportedVersion(schema_version));
+        }
+
+        serde_json::from_value::<VersionedData<Self>>(value)
+            .map(|vd| vd.data)
+            .map_err(|e| SchemaError::InvalidJson(e.to_string()))
+    }
+
+    /// Saves `self` as versioned JSON to `file_name`.
+    fn save(&self, file_name: &str) -> Result<(), SchemaError> {
+        fs::write(file_name, self.to_json()).map_err(|e| SchemaError::Io(e.to_string()))
+    }
+
+    /// Loads versioned JSON from `file_name`.
+    fn load(file_name: &str) -> Result<Self, SchemaError> {
+        let contents = fs::read_to_string(file_name).map_err(|e| SchemaError::Io(e.to_string()))?;
+        Self::from_json(&contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        crafting::{
+            cost_distribution::CostDistribution, craft_plan::CraftPlan, craft_step::CraftStep,
+            simulation_report::SimulationReport, starting_item::StartingItem,
+        },
+        datasets::affix_slots::AffixSlots,
+        items::{affix_side::AffixSide, item_state::ItemState, modifier::Modifier, rarity::Rarity},
+    };
+
+    fn item_state() -> ItemState {
+        let modifier = |id: &str, value: u16| Modifier {
+            name: String::new(),
+            id: id.to_owned(),
+            tier: 2,
+            value,
+            weight: 1000,
+            desecrated: false,
+            fractured: false,
+        };
+        let mut item_state = ItemState::new(
+            "Warlord Cuirass",
+            "chest",
+            AffixSlots::default_for(Rarity::Rare),
+            82,
+            vec![modifier("max-life", 180).as_fractured()],
+            vec![modifier("fire-res", 38)],
+        );
+        item_state.implicits = vec![modifier("armor-ele-dmg", 20)];
+        item_state.suffixes_targeted = true;
+        item_state.unrevealed_affix = Some(AffixSide::Prefix);
+        item_state.set_next_action(Some("reveal".to_owned()));
+        item_state
+    }
+
+    #[test]
+    fn item_state_round_trips() {
+        let item_state = item_state();
+
+        assert_eq!(ItemState::from_json(&item_state.to_json()), Ok(item_state));
+    }
+
+    #[test]
+    fn impossible_craft_plan_round_trips() {
+        let craft_plan = CraftPlan {
+            target: item_state(),
+            step: CraftStep::Craft {
+                start: StartingItem::new(item_state(), 1.5),
+                actions: vec!["chaos".to_owned()],
+            },
+            success_chance: 0.0,
+            expected_cost: f32::INFINITY,
+            cost_distribution: CostDistribution::from_costs(vec![]),
+        };
+
+        assert_eq!(CraftPlan::from_json(&craft_plan.to_json()), Ok(craft_plan));
+    }
+
+    #[test]
+    fn simulation_report_round_trips() {
+        let simulation_report = SimulationReport {
+            runs: 3,
+            starting_item: StartingItem::new(item_state(), 0.0),
+            successes: 1,
+            best_sequence: vec!["transmute".to_owned(), "regal".to_owned()],
+            best_cost: Some(12.5),
+            expected_salvage: 0.25,
+            cost_distribution: CostDistribution::from_costs(vec![30.0, 12.5, 20.0]),
+        };
+
+        assert_eq!(
+            SimulationReport::from_json(&simulation_report.to_json()),
+            Ok(simulation_report)
+        );
+    }
+
+    #[test]
+    fn rejects_another_schema_version() {
+        let json = item_state().to_json().replacen(
+            &format!("\"schema_version\": {SCHEMA_VERSION}"),
+            &format!("\"schema_version\": {}", SCHEMA_VERSION + 1),
+            1,
+        );
+
+        assert_eq!(
+            ItemState::from_json(&json),
+            Err(SchemaError::UnsupportedVersion(SCHEMA_VERSION + 1))
+        );
+    }
+
+    #[test]
+    fn rejects_a_missing_schema_version() {
+        let mut value =
+            serde_json::from_str::<serde_json::Value>(&item_state().to_json()).expect("valid JSON");
+        value
+            .as_object_mut()
+            .expect("versioned data is an object")
+            .remove("schema_version");
+
+        assert_eq!(
+            ItemState::from_json(&value.to_string()),
+            Err(SchemaError::MissingVersion)
+        );
+    }
+}
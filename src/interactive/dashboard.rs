@@ -0,0 +1,230 @@
+use crate::{
+    crafting::{
+        simulation_report::SimulationReport, simulation_run::SimulationRun,
+        solver_observer::SolverObserver,
+    },
+    datasets::prices::Prices,
+};
+use std::{
+    io::{self, Write},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// The least amount of time between redrawing the dashboard.
+const RENDER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The amount of buckets in the cost histogram.
+const HISTOGRAM_BUCKETS: usize = 10;
+
+/// The widest bar in the cost histogram, in characters.
+const HISTOGRAM_WIDTH: usize = 40;
+
+/// The widest progress bar, in characters.
+const PROGRESS_WIDTH: usize = 40;
+
+/// The progress of a simulation shown on a [`Dashboard`].
+#[derive(Default)]
+struct DashboardState {
+    runs: u32,
+    completed: u32,
+    successes: u32,
+    costs: Vec<f32>,
+    best_run: Option<SimulationRun>,
+    last_render: Option<Instant>,
+}
+
+/// A terminal dashboard showing the live progress of simulations, with costs
+/// reported in `currency`.
+pub struct Dashboard {
+    prices: Prices,
+    currency: String,
+    state: Mutex<DashboardState>,
+}
+
+impl Dashboard {
+    /// Makes a new [`Dashboard`].
+    pub fn new(prices: Prices, currency: &str) -> Self {
+        Self {
+            prices,
+            currency: currency.to_owned(),
+            state: Mutex::new(DashboardState::default()),
+        }
+    }
+
+    /// Redraws the dashboard, unless it was drawn recently and not `forced`.
+    fn render(&self, state: &mut DashboardState, forced: bool) {
+        if !forced
+            && state
+                .last_render
+                .is_some_and(|lr| lr.elapsed() < RENDER_INTERVAL)
+        {
+            return;
+        }
+        state.last_render = Some(Instant::now());
+
+        let progress = state.completed as f32 / state.runs.max(1) as f32;
+        let filled = (progress * PROGRESS_WIDTH as f32) as usize;
+        let mut lines = vec![
+            "craft-solver simulation".to_owned(),
+            String::new(),
+            format!(
+                "[{}{}] {}/{} runs",
+                "#".repeat(filled),
+                "-".repeat(PROGRESS_WIDTH - filled),
+                state.completed,
+                state.runs
+            ),
+            format!(
+                "success rate: {:.2}% ({} successes)",
+                state.successes as f32 / state.completed.max(1) as f32 * 100.0,
+                state.successes
+            ),
+        ];
+
+        match &state.best_run {
+            Some(best_run) => {
+                lines.push(format!(
+                    "best cost: {}",
+                    self.prices.format_cost(best_run.cost, &self.currency)
+                ));
+                lines.push(format!("best sequence: {:?}", best_run.sequence));
+            }
+            None => lines.push("best cost: none yet".to_owned()),
+        }
+
+        lines.push(String::new());
+        lines.push("run costs:".to_owned());
+        lines.extend(self.get_histogram_lines(&state.costs));
+
+        if let Some(best_run) = &state.best_run {
+            let item = &best_run.item;
+            lines.push(String::new());
+            lines.push(format!(
+                "best item: {} ({}, item level {})",
+                item.base, item.rarity, item.item_level
+            ));
+            for (affix_type, affixes) in [("prefix", &item.prefixes), ("suffix", &item.suffixes)] {
+                for modifier in affixes {
+                    lines.push(format!(
+                        "  {affix_type}: {} ({})",
+                        modifier.name, modifier.value
+                    ));
+                }
+            }
+        }
+
+        // clear the screen and draw from the top left corner
+        let mut stdout = io::stdout().lock();
+        let _ = writeln!(stdout, "\x1b[2J\x1b[H{}", lines.join("\n"));
+        let _ = stdout.flush();
+    }
+
+    /// Gets the lines of a histogram of `costs`, from cheapest to most expensive.
+    fn get_histogram_lines(&self, costs: &[f32]) -> Vec<String> {
+        let (Some(min), Some(max)) = (
+            costs.iter().copied().reduce(f32::min),
+            costs.iter().copied().reduce(f32::max),
+        ) else {
+            return vec!["  (no runs yet)".to_owned()];
+        };
+
+        let bucket_size = ((max - min) / HISTOGRAM_BUCKETS as f32).max(f32::EPSILON);
+        let mut counts = [0usize; HISTOGRAM_BUCKETS];
+        for cost in costs {
+            let bucket = ((cost - min) / bucket_size) as usize;
+            counts[bucket.min(HISTOGRAM_BUCKETS - 1)] += 1;
+        }
+
+        let most = counts.iter().copied().max().unwrap_or_default().max(1);
+        counts
+            .iter()
+            .enumerate()
+            .map(|(i, count)| {
+                format!(
+                    "  {:>24} | {} {count}",
+                    self.prices
+                        .format_cost(min + bucket_size * i as f32, &self.currency),
+                    "#".repeat(count * HISTOGRAM_WIDTH / most)
+                )
+            })
+            .collect()
+    }
+}
+
+impl SolverObserver for Dashboard {
+    fn on_simulation_started(&self, runs: u32) {
+        let mut state = self.state.lock().unwrap();
+        *state = DashboardState {
+            runs,
+            ..Default::default()
+        };
+        self.render(&mut state, true);
+    }
+
+    fn on_run_finished(&self, _run: u32, run: &SimulationRun) {
+        let mut state = self.state.lock().unwrap();
+        state.completed += 1;
+        state.successes += u32::from(run.succeeded);
+        state.costs.push(run.get_net_cost());
+        self.render(&mut state, false);
+    }
+
+    fn on_new_best(&self, _run: u32, run: &SimulationRun) {
+        let mut state = self.state.lock().unwrap();
+        // workers can report bests out of order, so a worse one may come last
+        if state
+            .best_run
+            .as_ref()
+            .is_some_and(|br| br.cost <= run.cost)
+        {
+            return;
+        }
+        state.best_run = Some(run.clone());
+        self.render(&mut state, false);
+    }
+
+    fn on_simulation_finished(&self, _report: &SimulationReport) {
+        let mut state = self.state.lock().unwrap();
+        self.render(&mut state, true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        datasets::affix_slots::AffixSlots,
+        files::from_file::FromFile,
+        items::{item_state::ItemState, rarity::Rarity},
+    };
+
+    fn run(cost: f32) -> SimulationRun {
+        SimulationRun {
+            item: ItemState::new(
+                "Warlord Cuirass",
+                "chest",
+                AffixSlots::default_for(Rarity::Rare),
+                82,
+                vec![],
+                vec![],
+            ),
+            sequence: vec![],
+            cost,
+            salvage: 0.0,
+            succeeded: true,
+        }
+    }
+
+    #[test]
+    fn keeps_the_cheapest_best_run_reported_out_of_order() {
+        let prices = Prices::default().populate("assets/prices.toml");
+        let dashboard = Dashboard::new(prices, "divine");
+
+        dashboard.on_new_best(2, &run(10.0));
+        dashboard.on_new_best(1, &run(20.0));
+
+        let state = dashboard.state.lock().unwrap();
+        assert_eq!(state.best_run.as_ref().map(|br| br.cost), Some(10.0));
+    }
+}
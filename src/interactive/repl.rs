@@ -0,0 +1,138 @@
+use crate::{
+    crafting::{crafting_session::CraftingSession, solver::Solver},
+    items::{item_state::ItemState, rarity::Rarity},
+};
+use logger::{log_info, log_warning};
+use std::io::{self, BufRead, Write};
+
+/// The amount of runs used to estimate the odds of each next action.
+const ODDS_RUNS: u32 = 100;
+
+/// The amount of crafting steps per run used to estimate odds.
+const ODDS_STEPS_PER_RUN: u32 = 20;
+
+/// Runs an interactive crafting sandbox on standard input, where a user loads
+/// a base and applies crafting actions towards `target_state` by id, seeing
+/// the item and the odds of each next action as they go.
+pub fn run(solver: &Solver, target_state: ItemState) {
+    let start_item = get_base_item(solver, &target_state.base, target_state.item_level)
+        .expect("the target must have a known item base");
+    let mut session = CraftingSession::new(solver, start_item, target_state);
+    log_info!("crafting sandbox started, type \"help\" for a list of commands.");
+    show_item(solver, &session);
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        io::stdout()
+            .flush()
+            .expect("could not flush standard output");
+        let Some(Ok(line)) = lines.next() else {
+            break;
+        };
+
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let argument = words.collect::<Vec<&str>>().join(" ");
+        match command {
+            "" => {}
+            "help" => show_help(),
+            "load" => {
+                // the item level may follow the base name, e.g.: "load Warlord Cuirass 82"
+                let (base, item_level) = match argument.rsplit_once(' ') {
+                    Some((base, level)) if level.parse::<u8>().is_ok() => {
+                        (base, level.parse().unwrap_or_default())
+                    }
+                    _ => (argument.as_str(), session.target.item_level),
+                };
+                match get_base_item(solver, base, item_level) {
+                    Some(item) => {
+                        session = CraftingSession::new(solver, item, session.target.clone());
+                        show_item(solver, &session);
+                    }
+                    None => log_warning!("unknown item base `{base}`."),
+                }
+            }
+            "paste" => {
+                log_info!("paste the copied item text, followed by an empty line.");
+                let text = lines
+                    .by_ref()
+                    .map_while(Result::ok)
+                    .take_while(|l| !l.trim().is_empty())
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                match solver.parse_item(&text) {
+                    Ok(item) => {
+                        session = CraftingSession::new(solver, item, session.target.clone());
+                        show_item(solver, &session);
+                    }
+                    Err(error) => log_warning!("could not parse the item: {error}"),
+                }
+            }
+            "apply" if session.apply(&argument) => show_item(solver, &session),
+            "apply" => log_warning!("`{argument}` can't be used on the item right now."),
+            "actions" => log_info!("valid actions: {:?}", session.get_valid_actions()),
+            "odds" => show_odds(solver, &session),
+            "undo" if session.undo() => show_item(solver, &session),
+            "undo" => log_warning!("there is nothing to undo."),
+            "redo" if session.redo() => show_item(solver, &session),
+            "redo" => log_warning!("there is nothing to redo."),
+            "show" => show_item(solver, &session),
+            "text" => log_info!("{}", solver.format_item(session.get_item())),
+            "target" => session.target.display(),
+            "quit" | "exit" => break,
+            _ => log_warning!("unknown command `{command}`, type \"help\" for a list of commands."),
+        }
+    }
+}
+
+/// Gets a new, normal item of a `base` by name.
+fn get_base_item(solver: &Solver, base: &str, item_level: u8) -> Option<ItemState> {
+    solver
+        .items
+        .items
+        .iter()
+        .find(|i| i.name.eq_ignore_ascii_case(base))
+        .map(|i| {
+            ItemState::new(
+                &i.name,
+                &i.class,
+                solver.items.get_affix_slots(&i.name, Rarity::Normal),
+                item_level,
+                vec![],
+                vec![],
+            )
+        })
+}
+
+/// Prints the item of a `session`, and either that it meets the target or
+/// the live odds of each next action.
+fn show_item(solver: &Solver, session: &CraftingSession) {
+    session.get_item().display();
+    if session.get_item().meets_target(&session.target) {
+        log_info!("the item meets the target!");
+    } else {
+        show_odds(solver, session);
+    }
+}
+
+/// Prints the odds of attaining the target after each next action.
+fn show_odds(solver: &Solver, session: &CraftingSession) {
+    for recommendation in session.get_odds(ODDS_RUNS, ODDS_STEPS_PER_RUN) {
+        recommendation.display(&solver.prices, &solver.report_currency);
+    }
+}
+
+/// Prints every command of the sandbox.
+fn show_help() {
+    log_info!("commands:");
+    log_info!("  load <base> [item level]  start over from a new, normal base");
+    log_info!("  paste                     start over from copied in-game item text");
+    log_info!("  apply <action id>         use a crafting action, e.g.: \"apply exalt\"");
+    log_info!("  actions                   list the actions that can be used");
+    log_info!("  odds                      show the odds of each next action again");
+    log_info!("  undo / redo               undo or redo the last action");
+    log_info!("  show / text / target      show the item, its game text, or the target");
+    log_info!("  quit                      leave the sandbox");
+}
@@ -0,0 +1,26 @@
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+/// Represents the side of an item that an affix rolls on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AffixSide {
+    Prefix,
+    Suffix,
+}
+
+impl AffixSide {
+    /// Gets the name of the affix side, e.g.: "prefix".
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Prefix => "prefix",
+            Self::Suffix => "suffix",
+        }
+    }
+}
+
+impl fmt::Display for AffixSide {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.get_name())
+    }
+}
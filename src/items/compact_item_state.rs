@@ -0,0 +1,229 @@
+use crate::{
+    datasets::affix_slots::AffixSlots,
+    items::{
+        affix_side::AffixSide, compact_modifier::CompactModifier, id_registry::IdRegistry,
+        interned_id::InternedId, item_state::ItemState, modifier::Modifier, rarity::Rarity,
+    },
+};
+use smallvec::SmallVec;
+
+/// The most affixes of a side kept inline, which covers the slots of every
+/// base in `assets/items.toml`. Items with more spill onto the heap.
+pub const INLINE_AFFIXES: usize = 3;
+
+/// Affixes of a compact item state, kept inline.
+pub type CompactAffixes = SmallVec<[CompactModifier; INLINE_AFFIXES]>;
+
+/// The most crafting actions a compact item state can expect next, as bits.
+const MAX_COMPACT_ACTIONS: u16 = u64::BITS as u16;
+
+const PREFIXES_TARGETED: u8 = 1;
+const SUFFIXES_TARGETED: u8 = 1 << 1;
+const LOWEST_TIER_TARGETED: u8 = 1 << 2;
+const DESECRATED_TARGETED: u8 = 1 << 3;
+
+/// An [`ItemState`] with interned ids and no names, that is cheap to clone
+/// and hash. Affixes are kept sorted, so items with the same modifiers in a
+/// different order are equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompactItemState {
+    pub base: InternedId,
+    pub class: InternedId,
+    pub rarity: Rarity,
+    pub max_prefixes: u8,
+    pub max_suffixes: u8,
+    pub item_level: u8,
+    pub implicits: CompactAffixes,
+    pub prefixes: CompactAffixes,
+    pub suffixes: CompactAffixes,
+    /// Which affixes are targeted by omens, as bits.
+    pub targets: u8,
+    pub unrevealed_affix: Option<AffixSide>,
+    /// The ids of the crafting actions expected next, as bits.
+    pub next_actions: u64,
+}
+
+impl CompactItemState {
+    /// Makes a new [`CompactItemState`] from `item_state`, or `None` if it
+    /// has ids missing from `registry` or more next actions than fit.
+    pub fn from_item(item_state: &ItemState, registry: &IdRegistry) -> Option<Self> {
+        let mut next_actions = 0;
+        for action in item_state.get_next_actions() {
+            let id = registry.actions.get_id(&action)?;
+            if id.0 >= MAX_COMPACT_ACTIONS {
+                return None;
+            }
+            next_actions |= 1 << id.0;
+        }
+
+        let targets = [
+            (item_state.prefixes_targeted, PREFIXES_TARGETED),
+            (item_state.suffixes_targeted, SUFFIXES_TARGETED),
+            (item_state.lowest_tier_targeted, LOWEST_TIER_TARGETED),
+            (item_state.desecrated_targeted, DESECRATED_TARGETED),
+        ]
+        .into_iter()
+        .filter(|(targeted, _)| *targeted)
+        .fold(0, |targets, (_, target)| targets | target);
+
+        Some(Self {
+            base: registry.bases.get_id(&item_state.base)?,
+            class: registry.classes.get_id(&item_state.class)?,
+            rarity: item_state.rarity,
+            max_prefixes: item_state.max_prefixes,
+            max_suffixes: item_state.max_suffixes,
+            item_level: item_state.item_level,
+            implicits: Self::compact_affixes(&item_state.implicits, registry)?,
+            prefixes: Self::compact_affixes(&item_state.prefixes, registry)?,
+            suffixes: Self::compact_affixes(&item_state.suffixes, registry)?,
+            targets,
+            unrevealed_affix: item_state.unrevealed_affix,
+            next_actions,
+        })
+    }
+
+    /// Compacts `modifiers`, sorted.
+    fn compact_affixes(modifiers: &[Modifier], registry: &IdRegistry) -> Option<CompactAffixes> {
+        let mut affixes = modifiers
+            .iter()
+            .map(|modifier| {
+                Some(CompactModifier {
+                    affix: registry.affixes.get_id(&modifier.id)?,
+                    tier: modifier.tier,
+                    value: modifier.value,
+                    weight: modifier.weight,
+                    desecrated: modifier.desecrated,
+                    fractured: modifier.fractured,
+                })
+            })
+            .collect::<Option<CompactAffixes>>()?;
+        affixes.sort();
+        Some(affixes)
+    }
+
+    /// Expands the compact item state back into an [`ItemState`].
+    pub fn to_item(&self, registry: &IdRegistry) -> ItemState {
+        let expand_affixes = |affixes: &[CompactModifier]| {
+            affixes
+                .iter()
+                .map(|a| Modifier {
+                    name: registry.get_affix_name(a.affix, a.value),
+                    id: registry.affixes.get_name(a.affix).to_owned(),
+                    tier: a.tier,
+                    value: a.value,
+                    weight: a.weight,
+                    desecrated: a.desecrated,
+                    fractured: a.fractured,
+                })
+                .collect::<Vec<Modifier>>()
+        };
+
+        let mut item_state = ItemState::new(
+            registry.bases.get_name(self.base),
+            registry.classes.get_name(self.class),
+            AffixSlots {
+                rarity: self.rarity,
+                prefixes: self.max_prefixes,
+                suffixes: self.max_suffixes,
+            },
+            self.item_level,
+            expand_affixes(&self.prefixes),
+            expand_affixes(&self.suffixes),
+        );
+        item_state.implicits = expand_affixes(&self.implicits);
+        item_state.prefixes_targeted = self.has_target(PREFIXES_TARGETED);
+        item_state.suffixes_targeted = self.has_target(SUFFIXES_TARGETED);
+        item_state.lowest_tier_targeted = self.has_target(LOWEST_TIER_TARGETED);
+        item_state.desecrated_targeted = self.has_target(DESECRATED_TARGETED);
+        item_state.unrevealed_affix = self.unrevealed_affix;
+        for id in 0..MAX_COMPACT_ACTIONS {
+            if self.next_actions & (1 << id) != 0 {
+                item_state
+                    .set_next_action(Some(registry.actions.get_name(InternedId(id)).to_owned()));
+            }
+        }
+
+        item_state
+    }
+
+    /// Determines if the `target` bit is set.
+    fn has_target(&self, target: u8) -> bool {
+        self.targets & target != 0
+    }
+
+    /// Gets the amount of prefixes on the item.
+    pub fn get_prefix_count(&self) -> usize {
+        self.prefixes.len()
+    }
+
+    /// Gets the amount of suffixes on the item.
+    pub fn get_suffix_count(&self) -> usize {
+        self.suffixes.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::INLINE_AFFIXES;
+    use crate::crafting::solver::Solver;
+    use crate::items::{item_state::ItemState, modifier::Modifier, rarity::Rarity};
+
+    fn item(solver: &Solver, prefixes: &[(&'static str, u16)]) -> ItemState {
+        ItemState::new(
+            "Warlord Cuirass",
+            "chest",
+            solver
+                .items
+                .get_affix_slots("Warlord Cuirass", Rarity::Rare),
+            82,
+            prefixes
+                .iter()
+                .map(|(id, value)| Modifier::from_value(solver, "chest", id, *value))
+                .collect(),
+            vec![Modifier::from_value(solver, "chest", "fire-res", 40)],
+        )
+    }
+
+    #[test]
+    fn compact_item_round_trips_more_than_three_affixes() {
+        let solver = Solver::new();
+        let mut item_state = item(
+            &solver,
+            &[("max-life", 150), ("armor", 80), ("flat-armor", 100)],
+        );
+        item_state.max_prefixes = 4;
+        item_state
+            .prefixes
+            .push(Modifier::from_value(&solver, "chest", "spirit", 30));
+
+        let compact_item = solver.compact_item(&item_state).expect("known ids");
+        let expanded_item = solver.expand_item(&compact_item);
+
+        assert_eq!(expanded_item.prefixes.len(), 4);
+        assert_eq!(solver.compact_item(&expanded_item), Some(compact_item));
+    }
+
+    #[test]
+    fn every_base_fits_its_affixes_inline() {
+        let solver = Solver::new();
+        for item in &solver.items.items {
+            for rarity in [Rarity::Normal, Rarity::Magic, Rarity::Rare] {
+                let slots = solver.items.get_affix_slots(&item.name, rarity);
+                assert!(slots.prefixes as usize <= INLINE_AFFIXES, "{}", item.name);
+                assert!(slots.suffixes as usize <= INLINE_AFFIXES, "{}", item.name);
+            }
+        }
+    }
+
+    #[test]
+    fn compact_item_ignores_affix_order() {
+        let solver = Solver::new();
+        let item_state = item(&solver, &[("max-life", 150), ("armor", 80)]);
+        let reordered_item = item(&solver, &[("armor", 80), ("max-life", 150)]);
+
+        assert_eq!(
+            solver.compact_item(&item_state),
+            solver.compact_item(&reordered_item)
+        );
+    }
+}
@@ -0,0 +1,13 @@
+use crate::items::interned_id::InternedId;
+
+/// A [`Modifier`](crate::items::modifier::Modifier) without its name, that
+/// is cheap to copy and hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CompactModifier {
+    pub affix: InternedId,
+    pub tier: u8,
+    pub value: u16,
+    pub weight: u16,
+    pub desecrated: bool,
+    pub fractured: bool,
+}
@@ -0,0 +1,51 @@
+use crate::items::interned_id::InternedId;
+use std::collections::HashMap;
+
+/// Maps strings to small [`InternedId`]s and back.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IdInterner {
+    names: Vec<String>,
+    ids: HashMap<String, InternedId>,
+}
+
+impl IdInterner {
+    /// Gets the id of `name`, interning it first if it's new.
+    pub fn intern(&mut self, name: &str) -> InternedId {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+
+        let id = InternedId(
+            self.names
+                .len()
+                .try_into()
+                .expect("could not intern more than 65536 ids."),
+        );
+        self.names.push(name.to_owned());
+        self.ids.insert(name.to_owned(), id);
+        id
+    }
+
+    /// Gets the id of `name`, if it was interned.
+    pub fn get_id(&self, name: &str) -> Option<InternedId> {
+        self.ids.get(name).copied()
+    }
+
+    /// Gets the name of `id`, defaults to an empty string.
+    pub fn get_name(&self, id: InternedId) -> &str {
+        self.names
+            .get(id.0 as usize)
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    /// Gets the amount of interned names.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Determines if nothing was interned.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
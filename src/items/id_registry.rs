@@ -0,0 +1,106 @@
+use crate::{
+    datasets::{
+        class_tiers::ClassTiers, craft_actions::CraftActions, items::Items, modifiers::Modifiers,
+    },
+    items::{
+        compact_item_state::INLINE_AFFIXES, id_interner::IdInterner, interned_id::InternedId,
+        item_state::ItemState,
+    },
+};
+use smallvec::SmallVec;
+
+/// The interned ids of every affix, action, class and base in the datasets.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IdRegistry {
+    pub affixes: IdInterner,
+    pub actions: IdInterner,
+    pub classes: IdInterner,
+    pub bases: IdInterner,
+    /// Affix names with "#" in place of their value, by affix id.
+    affix_names: Vec<String>,
+    /// The index of each action in the crafting actions, by action id.
+    action_indices: Vec<usize>,
+}
+
+impl IdRegistry {
+    /// Makes a new [`IdRegistry`] from the datasets.
+    pub fn new(
+        modifiers: &Modifiers,
+        craft_actions: &CraftActions,
+        items: &Items,
+        class_tiers: &ClassTiers,
+    ) -> Self {
+        let mut registry = Self::default();
+        for affix in &modifiers.modifiers {
+            let id = registry.intern_affix(&affix.id);
+            registry.affix_names[id.0 as usize].clone_from(&affix.name);
+        }
+        // affixes can have tiers without a named modifier
+        for class_tier in &class_tiers.class_tiers {
+            for affix_tier in class_tier.get_all_affixes() {
+                registry.intern_affix(&affix_tier.affix);
+            }
+            for class in &class_tier.classes {
+                registry.classes.intern(class);
+            }
+        }
+        for (index, action) in craft_actions.craft_actions.iter().enumerate() {
+            registry.actions.intern(&action.id);
+            // only the first action with an id is ever used
+            if registry.action_indices.len() < registry.actions.len() {
+                registry.action_indices.push(index);
+            }
+        }
+        for class in &items.classes {
+            registry.classes.intern(&class.id);
+        }
+        for item in &items.items {
+            registry.classes.intern(&item.class);
+            registry.bases.intern(&item.name);
+        }
+
+        registry
+    }
+
+    /// Gets the id of `affix`, interning it first if it's new.
+    pub fn intern_affix(&mut self, affix: &str) -> InternedId {
+        let id = self.affixes.intern(affix);
+        if self.affix_names.len() < self.affixes.len() {
+            self.affix_names.push(String::new());
+        }
+        id
+    }
+
+    /// Gets the index of the crafting `action` in the crafting actions, if
+    /// there is one.
+    pub fn get_action_index(&self, action: InternedId) -> Option<usize> {
+        self.action_indices.get(action.0 as usize).copied()
+    }
+
+    /// Gets the id of every crafting action.
+    pub fn get_action_ids(&self) -> impl Iterator<Item = InternedId> + use<> {
+        (0..self.actions.len()).map(|i| InternedId(i as u16))
+    }
+
+    /// Gets the ids of every prefix and suffix on `item_state` that is in the
+    /// datasets.
+    pub fn get_affix_ids(
+        &self,
+        item_state: &ItemState,
+    ) -> SmallVec<[InternedId; INLINE_AFFIXES * 2]> {
+        item_state
+            .prefixes
+            .iter()
+            .chain(item_state.suffixes.iter())
+            .filter_map(|m| self.affixes.get_id(&m.id))
+            .collect()
+    }
+
+    /// Gets the name of an `affix` with `value`, defaults to an empty string.
+    pub fn get_affix_name(&self, affix: InternedId, value: u16) -> String {
+        self.affix_names
+            .get(affix.0 as usize)
+            .map(|name| name.replace("#", &value.to_string()))
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,5 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// A small id standing in for an interned string, e.g.: an affix or action id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct InternedId(pub u16);
@@ -1,24 +1,31 @@
-use std::collections::HashMap;
-
-use crate::items::modifier::Modifier;
+use crate::{
+    datasets::{affix_slots::AffixSlots, outcome_affix::OutcomeAffix},
+    files::versioned::Versioned,
+    items::{affix_side::AffixSide, modifier::Modifier, rarity::Rarity},
+};
 use logger::log_info;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Represents the state of an item.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ItemState {
     pub base: String,
     pub class: String,
-    pub rarity: String,
+    pub rarity: Rarity,
     pub max_prefixes: u8,
     pub max_suffixes: u8,
     pub item_level: u8,
+    #[serde(default)]
+    pub implicits: Vec<Modifier>,
     pub prefixes: Vec<Modifier>,
     pub suffixes: Vec<Modifier>,
     pub prefixes_targeted: bool,
     pub suffixes_targeted: bool,
     pub lowest_tier_targeted: bool,
+    pub desecrated_targeted: bool,
+    pub unrevealed_affix: Option<AffixSide>,
     pub next_actions: Option<Vec<String>>,
-    // TODO: add a field for active Omens
 }
 
 impl ItemState {
@@ -26,7 +33,7 @@ impl ItemState {
     pub fn new(
         base: &str,
         class: &str,
-        rarity: &str,
+        slots: AffixSlots,
         item_level: u8,
         prefixes: Vec<Modifier>,
         suffixes: Vec<Modifier>,
@@ -34,37 +41,56 @@ impl ItemState {
         Self {
             base: base.to_owned(),
             class: class.to_owned(),
-            rarity: rarity.to_owned(),
-            max_prefixes: 3,
-            max_suffixes: 3,
+            rarity: slots.rarity,
+            max_prefixes: slots.prefixes,
+            max_suffixes: slots.suffixes,
             item_level,
+            implicits: vec![],
             prefixes,
             suffixes,
             prefixes_targeted: false,
             suffixes_targeted: false,
             lowest_tier_targeted: false,
+            desecrated_targeted: false,
+            unrevealed_affix: None,
             next_actions: None,
         }
     }
 
     /// Prints a user-friendly representation of an item state.
     pub fn display(&self) {
-        let base_rarity_length = self.base.len() + self.rarity.len();
+        let base_rarity_length = self.base.len() + self.rarity.get_name().len();
         log_info!("--- {} ({}) ---", self.base, self.rarity);
         log_info!("Item Level: {}", self.item_level);
         if !self.prefixes.is_empty() {
             log_info!("Prefixes:");
             for affix in &self.prefixes {
-                log_info!("  - {} ({})", affix.name, affix.value);
+                log_info!(
+                    "  - {} ({}){}{}",
+                    affix.name,
+                    affix.value,
+                    affix.get_desecrated_label(),
+                    affix.get_fractured_label()
+                );
             }
         }
         if !self.suffixes.is_empty() {
             log_info!("Suffixes:");
             for affix in &self.suffixes {
-                log_info!("  - {} ({})", affix.name, affix.value);
+                log_info!(
+                    "  - {} ({}){}{}",
+                    affix.name,
+                    affix.value,
+                    affix.get_desecrated_label(),
+                    affix.get_fractured_label()
+                );
             }
         }
-        if self.prefixes.is_empty() && self.suffixes.is_empty() {
+        if let Some(affix) = &self.unrevealed_affix {
+            log_info!("Unrevealed:");
+            log_info!("  - desecrated {affix}");
+        }
+        if self.prefixes.is_empty() && self.suffixes.is_empty() && !self.has_unrevealed_affix() {
             log_info!("  (No affixes)");
         }
         log_info!("{:-^width$}", "", width = base_rarity_length + 8);
@@ -88,11 +114,12 @@ impl ItemState {
 
     /// Predicate that determines if `a` is at least `b`.
     pub fn meets_modifier(&self, a: &Modifier, b: &Modifier) -> bool {
-        a.id == b.id && a.value >= b.value && a.tier >= b.tier
+        // a higher value of the same affix is always an equal or better tier
+        a.id == b.id && a.value >= b.value
     }
 
     /// Gets all "good" modifiers that meet the `target` item state.
-    pub fn get_good_modifiers(&self, target: &ItemState) -> HashMap<String, Vec<Modifier>> {
+    pub fn get_good_modifiers(&self, target: &ItemState) -> HashMap<AffixSide, Vec<Modifier>> {
         let good_prefixes = Vec::from_iter(self.prefixes.clone().into_iter().filter(|prefix| {
             target
                 .prefixes
@@ -110,18 +137,59 @@ impl ItemState {
 
         if !good_prefixes.is_empty() && !good_suffixes.is_empty() {
             HashMap::from([
-                ("prefix".to_owned(), good_prefixes),
-                ("suffix".to_owned(), good_suffixes),
+                (AffixSide::Prefix, good_prefixes),
+                (AffixSide::Suffix, good_suffixes),
             ])
         } else if !good_prefixes.is_empty() {
-            HashMap::from([("prefix".to_owned(), good_prefixes)])
+            HashMap::from([(AffixSide::Prefix, good_prefixes)])
         } else if !good_suffixes.is_empty() {
-            HashMap::from([("suffix".to_owned(), good_suffixes)])
+            HashMap::from([(AffixSide::Suffix, good_suffixes)])
         } else {
             HashMap::new()
         }
     }
 
+    /// Gets every way of splitting the modifiers of the item state into two
+    /// non-empty halves, as pairs of item states.
+    pub fn get_target_splits(&self) -> Vec<(ItemState, ItemState)> {
+        let modifier_count = self.prefixes.len() + self.suffixes.len();
+        if modifier_count < 2 {
+            return vec![];
+        }
+
+        // the first modifier always stays on the left, to skip mirrored splits
+        (1..(1u32 << (modifier_count - 1)))
+            .map(|mask| {
+                let is_left = |index: usize| index == 0 || mask & (1 << (index - 1)) == 0;
+                let split = |on_left: bool| {
+                    let prefixes = self
+                        .prefixes
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| is_left(*i) == on_left)
+                        .map(|(_, m)| m.clone())
+                        .collect::<Vec<Modifier>>();
+                    let suffixes = self
+                        .suffixes
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| is_left(i + self.prefixes.len()) == on_left)
+                        .map(|(_, m)| m.clone())
+                        .collect::<Vec<Modifier>>();
+                    ItemState::new(
+                        &self.base,
+                        &self.class,
+                        self.get_affix_slots(),
+                        self.item_level,
+                        prefixes,
+                        suffixes,
+                    )
+                };
+                (split(true), split(false))
+            })
+            .collect()
+    }
+
     /// Gets the next action expected by the item state, defaults to an empty string.
     pub fn get_next_actions(&self) -> Vec<String> {
         if self.has_next_action() {
@@ -153,21 +221,40 @@ impl ItemState {
         .is_empty()
     }
 
-    pub fn target_affixes(&mut self, affixes: &str) {
+    pub fn target_affixes(&mut self, affixes: OutcomeAffix) {
         match affixes {
-            "prefix" => {
+            OutcomeAffix::Prefix => {
                 self.prefixes_targeted = true;
             }
-            "suffix" => {
+            OutcomeAffix::Suffix => {
                 self.suffixes_targeted = true;
             }
-            "lowest" => {
+            OutcomeAffix::Lowest => {
                 self.lowest_tier_targeted = true;
             }
-            _ => {}
+            OutcomeAffix::Desecrated => {
+                self.desecrated_targeted = true;
+            }
+            OutcomeAffix::Random => {}
+        }
+    }
+
+    /// Gets the rarity of the item, along with the most affixes it can have.
+    pub fn get_affix_slots(&self) -> AffixSlots {
+        AffixSlots {
+            rarity: self.rarity,
+            prefixes: self.max_prefixes,
+            suffixes: self.max_suffixes,
         }
     }
 
+    /// Sets the rarity of the item, along with the most affixes it can have.
+    pub fn set_affix_slots(&mut self, slots: &AffixSlots) {
+        self.rarity = slots.rarity;
+        self.max_prefixes = slots.prefixes;
+        self.max_suffixes = slots.suffixes;
+    }
+
     pub fn has_lowest_tier_targeted(&self) -> bool {
         self.lowest_tier_targeted
     }
@@ -177,7 +264,8 @@ impl ItemState {
     }
 
     pub fn has_max_prefixes(&self) -> bool {
-        self.prefixes.len() >= self.max_prefixes.into()
+        self.prefixes.len() + self.get_unrevealed_count(AffixSide::Prefix)
+            >= self.max_prefixes.into()
     }
 
     pub fn has_no_prefixes(&self) -> bool {
@@ -185,7 +273,8 @@ impl ItemState {
     }
 
     pub fn has_max_suffixes(&self) -> bool {
-        self.suffixes.len() >= self.max_suffixes.into()
+        self.suffixes.len() + self.get_unrevealed_count(AffixSide::Suffix)
+            >= self.max_suffixes.into()
     }
 
     pub fn has_no_suffixes(&self) -> bool {
@@ -200,6 +289,21 @@ impl ItemState {
         self.has_max_prefixes() && self.has_max_suffixes()
     }
 
+    /// Determines if every prefix is fractured, so none can be removed.
+    pub fn has_no_removable_prefixes(&self) -> bool {
+        self.prefixes.iter().all(|m| m.fractured)
+    }
+
+    /// Determines if every suffix is fractured, so none can be removed.
+    pub fn has_no_removable_suffixes(&self) -> bool {
+        self.suffixes.iter().all(|m| m.fractured)
+    }
+
+    /// Determines if every modifier is fractured, so none can be removed.
+    pub fn has_no_removable_affixes(&self) -> bool {
+        self.has_no_removable_prefixes() && self.has_no_removable_suffixes()
+    }
+
     pub fn has_next_action(&self) -> bool {
         self.next_actions.clone().is_some_and(|x| !x.is_empty())
     }
@@ -230,25 +334,103 @@ impl ItemState {
         self.lowest_tier_targeted
     }
 
-    pub fn clear_affix_target(&mut self, affix: String) {
-        if affix.eq("prefix") {
-            self.prefixes_targeted = false;
-        } else {
-            self.suffixes_targeted = false;
+    pub fn has_targeted_desecrated(&self) -> bool {
+        self.desecrated_targeted
+    }
+
+    pub fn clear_desecrated_target(&mut self) {
+        self.desecrated_targeted = false;
+    }
+
+    /// Determines if the item has an unrevealed desecrated modifier.
+    pub fn has_unrevealed_affix(&self) -> bool {
+        self.unrevealed_affix.is_some()
+    }
+
+    /// Determines if the item has any revealed desecrated modifiers.
+    pub fn has_desecrated_affixes(&self) -> bool {
+        self.prefixes
+            .iter()
+            .chain(self.suffixes.iter())
+            .any(|m| m.desecrated)
+    }
+
+    /// Gets the amount of unrevealed modifiers occupying an `affix` slot.
+    pub fn get_unrevealed_count(&self, affix: AffixSide) -> usize {
+        (self.unrevealed_affix == Some(affix)).into()
+    }
+
+    pub fn clear_affix_target(&mut self, affix: AffixSide) {
+        match affix {
+            AffixSide::Prefix => self.prefixes_targeted = false,
+            AffixSide::Suffix => self.suffixes_targeted = false,
         }
     }
 
     pub fn clear_next_action(&mut self, action: String) {
-        if let Some(na) = self.next_actions.as_mut() {
-            if let Some(index) = na.iter().position(|a| a.eq(&action)) {
-                na.swap_remove(index);
-            }
+        if let Some(na) = self.next_actions.as_mut()
+            && let Some(index) = na.iter().position(|a| a.eq(&action))
+        {
+            na.swap_remove(index);
         }
     }
 
     pub fn get_affix_count(&self) -> u16 {
-        (self.prefixes.len() + self.suffixes.len())
+        (self.prefixes.len() + self.suffixes.len() + usize::from(self.has_unrevealed_affix()))
             .try_into()
             .expect("could not get item's affix count as an unsigned 16-bit integer.")
     }
 }
+
+impl Versioned for ItemState {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modifier(id: &str) -> Modifier {
+        Modifier {
+            name: String::new(),
+            id: id.to_owned(),
+            tier: 1,
+            value: 0,
+            weight: 0,
+            desecrated: false,
+            fractured: false,
+        }
+    }
+
+    fn item(prefixes: &[&str], suffixes: &[&str]) -> ItemState {
+        ItemState::new(
+            "Warlord Cuirass",
+            "chest",
+            AffixSlots::default_for(Rarity::Rare),
+            82,
+            prefixes.iter().map(|id| modifier(id)).collect(),
+            suffixes.iter().map(|id| modifier(id)).collect(),
+        )
+    }
+
+    #[test]
+    fn two_modifier_target_splits_once_into_single_modifiers() {
+        let splits = item(&["max-life"], &["fire-res"]).get_target_splits();
+
+        assert_eq!(splits.len(), 1);
+        let (left, right) = &splits[0];
+        assert_eq!(left.prefixes, vec![modifier("max-life")]);
+        assert!(left.suffixes.is_empty());
+        assert!(right.prefixes.is_empty());
+        assert_eq!(right.suffixes, vec![modifier("fire-res")]);
+    }
+
+    #[test]
+    fn target_splits_are_non_empty_and_not_mirrored() {
+        let splits = item(&["max-life", "armor"], &["fire-res"]).get_target_splits();
+
+        assert_eq!(splits.len(), 3);
+        for (left, right) in &splits {
+            assert!(left.prefixes.contains(&modifier("max-life")));
+            assert!(!right.prefixes.is_empty() || !right.suffixes.is_empty());
+        }
+    }
+}
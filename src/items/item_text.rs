@@ -0,0 +1,595 @@
+use crate::{
+    crafting::crafter::Crafter,
+    datasets::{class_tier::ClassTier, item::Item, items::Items, modifiers::Modifiers},
+    items::{
+        affix_side::AffixSide, item_state::ItemState, item_text_error::ItemTextError,
+        modifier::Modifier, rarity::Rarity,
+    },
+};
+use logger::log_debug;
+
+/// The line between sections of in-game item text.
+const SECTION_SEPARATOR: &str = "--------";
+
+/// The start of the line that stands in for an unrevealed desecrated
+/// modifier, followed by its affix side.
+const UNREVEALED_LINE: &str = "Unrevealed Desecrated";
+
+/// The start of lines in sections after the item level that hold no
+/// modifiers, e.g.: flags like "Corrupted".
+const NON_MODIFIER_LINES: [&str; 6] = [
+    "Corrupted",
+    "Fractured Item",
+    "Mirrored",
+    "Unidentified",
+    "Sockets:",
+    "Note:",
+];
+
+/// Tags at the end of modifier lines that are never parsed.
+const IGNORED_MODIFIER_TAGS: [&str; 2] = ["(enchant)", "(rune)"];
+
+/// Responsible for converting items to and from in-game item text.
+pub trait ItemText: Crafter {
+    /// Parses in-game item text, as copied with Ctrl+C (or Ctrl+Alt+C for
+    /// advanced modifier descriptions), into an [`ItemState`].
+    fn parse_item_text(
+        &self,
+        items: &Items,
+        class_tiers: &[ClassTier],
+        modifiers: &Modifiers,
+        text: &str,
+    ) -> Result<ItemState, ItemTextError> {
+        let lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<&str>>();
+
+        let rarity = lines
+            .iter()
+            .find_map(|l| l.strip_prefix("Rarity:"))
+            .ok_or(ItemTextError::MissingRarity)?
+            .trim()
+            .to_lowercase();
+        let rarity = Rarity::from_name(&rarity).ok_or(ItemTextError::UnknownRarity(rarity))?;
+        let item = self
+            .find_item_base(items, rarity, text)
+            .ok_or(ItemTextError::UnknownBase)?;
+        let item_level = lines
+            .iter()
+            .find_map(|l| l.strip_prefix("Item Level:"))
+            .ok_or(ItemTextError::MissingItemLevel)?
+            .trim();
+        let item_level = item_level
+            .parse::<u8>()
+            .map_err(|_| ItemTextError::InvalidItemLevel(item_level.to_owned()))?;
+
+        let mut item_state = ItemState::new(
+            &item.name,
+            &item.class,
+            items.get_affix_slots(&item.name, rarity),
+            item_level,
+            vec![],
+            vec![],
+        );
+
+        // tiers are resolved through the class of the base, e.g.: the same
+        // value can be a different tier on a jewel than on a chest
+        let unknown_class_tier = ClassTier::default();
+        let class_tier = class_tiers
+            .iter()
+            .find(|ct| ct.classes.iter().any(|c| c.eq(&item.class)))
+            .unwrap_or(&unknown_class_tier);
+
+        // every section after the item level holds modifiers, except for
+        // flags like "Corrupted", while properties and requirements come before
+        let mut is_after_item_level = false;
+        for section in text.split(SECTION_SEPARATOR) {
+            let section = section
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .collect::<Vec<&str>>();
+            if !is_after_item_level {
+                is_after_item_level = section.iter().any(|l| l.starts_with("Item Level:"));
+                continue;
+            }
+            let is_modifier_section = !section
+                .iter()
+                .all(|l| NON_MODIFIER_LINES.iter().any(|nml| l.starts_with(nml)));
+            if is_modifier_section {
+                self.parse_modifier_section(class_tier, modifiers, &section, &mut item_state)?;
+            }
+        }
+
+        Ok(item_state)
+    }
+
+    /// Finds the base named in the first section of item `text`, either on
+    /// its own line or within the name of a magic item, e.g.: "Hale Warlord
+    /// Cuirass of the Whale". The longest name wins when several match.
+    fn find_item_base<'a>(&self, items: &'a Items, rarity: Rarity, text: &str) -> Option<&'a Item> {
+        let name_lines = text
+            .split(SECTION_SEPARATOR)
+            .next()
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .collect::<Vec<&str>>();
+        let is_base_line = |line: &str, name: &str| {
+            if line == name {
+                return true;
+            }
+            let name_part = line.split(" of ").next().unwrap_or(line);
+            rarity == Rarity::Magic
+                && (name_part == name || name_part.ends_with(&format!(" {name}")))
+        };
+        items
+            .items
+            .iter()
+            .filter(|i| name_lines.iter().any(|l| is_base_line(l, &i.name)))
+            .max_by_key(|i| i.name.len())
+    }
+
+    /// Parses a `section` of modifier lines into `item_state`, failing on
+    /// any line that isn't a known modifier.
+    fn parse_modifier_section(
+        &self,
+        class_tier: &ClassTier,
+        modifiers: &Modifiers,
+        section: &[&str],
+        item_state: &mut ItemState,
+    ) -> Result<(), ItemTextError> {
+        // advanced descriptions head each modifier with a line like:
+        // { Prefix Modifier "Hale" (Tier: 1) — Life }
+        let mut header: Option<&str> = None;
+        let mut index = 0;
+        while index < section.len() {
+            let line = section[index];
+            index += 1;
+            if line.starts_with('{') {
+                header = Some(line);
+                continue;
+            }
+            if IGNORED_MODIFIER_TAGS.iter().any(|t| line.ends_with(t)) {
+                header = None;
+                continue;
+            }
+            if let Some(affix_side) = line.strip_prefix(UNREVEALED_LINE) {
+                let affix_side = match affix_side.trim() {
+                    "Prefix" => AffixSide::Prefix,
+                    "Suffix" => AffixSide::Suffix,
+                    _ => return Err(ItemTextError::UnknownModifier(line.to_owned())),
+                };
+                log_debug!("parsed unrevealed {affix_side}");
+                item_state.unrevealed_affix = Some(affix_side);
+                item_state.set_next_action(Some("reveal".to_owned()));
+                header = None;
+                continue;
+            }
+
+            let Some((mut modifier, line_count)) =
+                self.match_modifier_lines(class_tier, modifiers, &section[index - 1..])
+            else {
+                return Err(ItemTextError::UnknownModifier(line.to_owned()));
+            };
+            index += line_count - 1;
+
+            let tags = format!("{} {line}", header.unwrap_or_default()).to_lowercase();
+            let has_tag = |tag: &str| tags.split(|c: char| !c.is_alphabetic()).any(|w| w == tag);
+            header = None;
+            if has_tag("implicit") {
+                log_debug!("parsed implicit: {}", modifier.name);
+                item_state.implicits.push(modifier);
+                continue;
+            }
+
+            let is_abyssal = |affix_side| {
+                class_tier
+                    .get_abyssal_affixes(affix_side)
+                    .iter()
+                    .any(|a| a.affix.eq(&modifier.id))
+            };
+            let is_affix_side = |affix_side| {
+                class_tier
+                    .get_affixes(affix_side)
+                    .iter()
+                    .any(|a| a.affix.eq(&modifier.id))
+                    || is_abyssal(affix_side)
+            };
+            let affix_side = if has_tag("prefix") {
+                AffixSide::Prefix
+            } else if has_tag("suffix") {
+                AffixSide::Suffix
+            } else if is_affix_side(AffixSide::Prefix) {
+                AffixSide::Prefix
+            } else if is_affix_side(AffixSide::Suffix) {
+                AffixSide::Suffix
+            } else {
+                return Err(ItemTextError::UnknownAffixSide(modifier.id));
+            };
+
+            modifier.desecrated = has_tag("desecrated") || is_abyssal(affix_side);
+            modifier.fractured = has_tag("fractured");
+            log_debug!("parsed {affix_side}: {}", modifier.name);
+            match affix_side {
+                AffixSide::Prefix => item_state.prefixes.push(modifier),
+                AffixSide::Suffix => item_state.suffixes.push(modifier),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Matches the first of `lines` (and any following lines for hybrid
+    /// modifiers) to a modifier, and returns it with the amount of lines used.
+    fn match_modifier_lines(
+        &self,
+        class_tier: &ClassTier,
+        modifiers: &Modifiers,
+        lines: &[&str],
+    ) -> Option<(Modifier, usize)> {
+        let mut affixes = modifiers.modifiers.iter().collect::<Vec<_>>();
+        // hybrid modifiers span a line for each part, so they're matched first
+        affixes.sort_by_key(|a| std::cmp::Reverse(a.name.split(", ").count()));
+
+        affixes.into_iter().find_map(|affix| {
+            let line_count = affix.name.split(", ").count();
+            if line_count > lines.len() {
+                return None;
+            }
+            let text = lines[..line_count]
+                .iter()
+                .map(|l| self.clean_modifier_line(l))
+                .collect::<Vec<String>>()
+                .join(", ");
+            let values = self.match_modifier_template(&affix.name, &text)?;
+            let value = *values.first()?;
+            let tier = self.get_affix_tier(class_tier, &affix.id, value);
+            Some((
+                Modifier {
+                    name: text,
+                    id: affix.id.clone(),
+                    tier,
+                    value,
+                    weight: self.get_affix_tier_weight(class_tier, &affix.id, tier),
+                    desecrated: false,
+                    fractured: false,
+                },
+                line_count,
+            ))
+        })
+    }
+
+    /// Cleans a modifier line of game text, removing its tags and value
+    /// ranges, e.g.: "+180(175-189) to maximum Life (fractured)" becomes
+    /// "+180 to maximum life".
+    fn clean_modifier_line(&self, line: &str) -> String {
+        let mut cleaned = String::new();
+        let mut in_parentheses = false;
+        for c in line.chars() {
+            match c {
+                '(' => in_parentheses = true,
+                ')' => in_parentheses = false,
+                _ if !in_parentheses => cleaned.push(c),
+                _ => {}
+            }
+        }
+        // the game uses british spelling, where modifiers use american
+        cleaned.trim().to_lowercase().replace("armour", "armor")
+    }
+
+    /// Matches `text` to a modifier `template`, where every "#" is a number,
+    /// and returns the numbers in order, or nothing if it doesn't match.
+    fn match_modifier_template(&self, template: &str, text: &str) -> Option<Vec<u16>> {
+        let mut values = vec![];
+        let mut text_chars = text.chars().peekable();
+        for template_char in template.to_lowercase().chars() {
+            if template_char == '#' {
+                let mut number = String::new();
+                while let Some(c) = text_chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                    number.push(c);
+                }
+                values.push(number.parse::<f32>().ok()? as u16);
+            } else if text_chars.next() != Some(template_char) {
+                return None;
+            }
+        }
+        text_chars.next().is_none().then_some(values)
+    }
+
+    /// Formats `item_state` as in-game item text, with advanced modifier
+    /// descriptions of each explicit modifier's tier.
+    fn format_item_text(
+        &self,
+        items: &Items,
+        modifiers: &Modifiers,
+        item_state: &ItemState,
+    ) -> String {
+        let class_name = items
+            .classes
+            .iter()
+            .find(|c| c.id.eq(&item_state.class))
+            .map_or(item_state.class.as_str(), |c| c.name.as_str());
+        let mut rarity = item_state.rarity.to_string();
+        if let Some(first) = rarity.get_mut(..1) {
+            first.make_ascii_uppercase();
+        }
+
+        let mut sections = vec![
+            format!(
+                "Item Class: {class_name}\nRarity: {rarity}\n{}",
+                item_state.base
+            ),
+            format!("Item Level: {}", item_state.item_level),
+        ];
+
+        let implicits = item_state
+            .implicits
+            .iter()
+            .map(|implicit| {
+                format!(
+                    "{} (implicit)",
+                    self.get_modifier_text(modifiers, implicit)
+                        .replace(", ", "\n")
+                )
+            })
+            .collect::<Vec<String>>();
+        if !implicits.is_empty() {
+            sections.push(implicits.join("\n"));
+        }
+
+        let mut explicits = vec![];
+        for (affix_side, affix_type, affixes) in [
+            (AffixSide::Prefix, "Prefix", &item_state.prefixes),
+            (AffixSide::Suffix, "Suffix", &item_state.suffixes),
+        ] {
+            for modifier in affixes {
+                let desecrated = if modifier.desecrated {
+                    "Desecrated "
+                } else {
+                    ""
+                };
+                let fractured = if modifier.fractured {
+                    " (fractured)"
+                } else {
+                    ""
+                };
+                explicits.push(format!(
+                    "{{ {desecrated}{affix_type} Modifier (Tier: {}) }}\n{}{fractured}",
+                    modifier.tier,
+                    self.get_modifier_text(modifiers, modifier)
+                        .replace(", ", "\n")
+                ));
+            }
+            if item_state.unrevealed_affix == Some(affix_side) {
+                explicits.push(format!("{UNREVEALED_LINE} {affix_type}"));
+            }
+        }
+        if !explicits.is_empty() {
+            sections.push(explicits.join("\n"));
+        }
+
+        sections.join(&format!("\n{SECTION_SEPARATOR}\n")) + "\n"
+    }
+
+    /// Gets the text of a `modifier`, filling its template with its value if
+    /// it has no name.
+    fn get_modifier_text(&self, modifiers: &Modifiers, modifier: &Modifier) -> String {
+        if !modifier.name.is_empty() {
+            return modifier.name.clone();
+        }
+        modifiers
+            .modifiers
+            .iter()
+            .find(|m| m.id.eq(&modifier.id))
+            .map_or(modifier.id.clone(), |m| {
+                m.name.replace("#", &modifier.value.to_string())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        crafting::solver::Solver,
+        items::{
+            affix_side::AffixSide, item_text_error::ItemTextError, modifier::Modifier,
+            rarity::Rarity,
+        },
+    };
+
+    const COPIED_ITEM: &str = "Item Class: Body Armours
+Rarity: Rare
+Dusk Shell
+Warlord Cuirass
+--------
+Armour: 1020 (augmented)
+--------
+Requirements:
+Level: 80
+Str: 220
+--------
+Item Level: 82
+--------
++20% of Armour also applies to Elemental Damage (implicit)
+--------
++180 to maximum Life
++38% to Fire Resistance
++12% to Chaos Resistance
+--------
+Corrupted
+";
+
+    const COPIED_ADVANCED_ITEM: &str = "Item Class: Body Armours
+Rarity: Rare
+Dusk Shell
+Warlord Cuirass
+--------
+Armour: 1020 (augmented)
+--------
+Requirements:
+Level: 80
+Str: 220
+--------
+Item Level: 82
+--------
+{ Implicit Modifier }
++20(15-25)% of Armour also applies to Elemental Damage (implicit)
+--------
+{ Prefix Modifier \"Prime\" (Tier: 1) — Life }
++205(200-214) to maximum Life (fractured)
+{ Desecrated Suffix Modifier \"of the Abyss\" (Tier: 1) — Chaos, Resistance }
++25(24-27)% to Chaos Resistance
+--------
+Fractured Item
+";
+
+    fn ids(modifiers: &[Modifier]) -> Vec<(&str, u16)> {
+        modifiers.iter().map(|m| (m.id.as_str(), m.value)).collect()
+    }
+
+    #[test]
+    fn parses_copied_item_text() {
+        let item = Solver::new().parse_item(COPIED_ITEM).unwrap();
+
+        assert_eq!(item.base, "Warlord Cuirass");
+        assert_eq!(item.class, "chest");
+        assert_eq!(item.rarity, Rarity::Rare);
+        assert_eq!(item.item_level, 82);
+        assert_eq!(ids(&item.implicits), vec![("armor-ele-dmg", 20)]);
+        assert_eq!(ids(&item.prefixes), vec![("max-life", 180)]);
+        assert_eq!(
+            ids(&item.suffixes),
+            vec![("fire-res", 38), ("chaos-res", 12)]
+        );
+    }
+
+    #[test]
+    fn parses_copied_advanced_item_text() {
+        let item = Solver::new().parse_item(COPIED_ADVANCED_ITEM).unwrap();
+
+        assert_eq!(ids(&item.implicits), vec![("armor-ele-dmg", 20)]);
+        assert_eq!(ids(&item.prefixes), vec![("max-life", 205)]);
+        assert!(item.prefixes[0].fractured);
+        assert!(!item.prefixes[0].desecrated);
+        assert_eq!(ids(&item.suffixes), vec![("chaos-res", 25)]);
+        assert!(item.suffixes[0].desecrated);
+        assert!(!item.suffixes[0].fractured);
+    }
+
+    #[test]
+    fn fails_on_unknown_modifier_lines() {
+        let text = COPIED_ITEM.replace("+12% to Chaos Resistance", "+30% increased Movement Speed");
+
+        assert_eq!(
+            Solver::new().parse_item(&text),
+            Err(ItemTextError::UnknownModifier(
+                "+30% increased Movement Speed".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn fails_on_a_section_of_only_unknown_modifier_lines() {
+        let text = COPIED_ITEM.replace(
+            "+180 to maximum Life\n+38% to Fire Resistance\n+12% to Chaos Resistance",
+            "+30% increased Movement Speed\n+10% increased Rarity of Items found",
+        );
+
+        assert_eq!(
+            Solver::new().parse_item(&text),
+            Err(ItemTextError::UnknownModifier(
+                "+30% increased Movement Speed".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn finds_the_base_line_instead_of_a_base_within_the_item_name() {
+        let text = COPIED_ITEM.replace("Dusk Shell", "Emerald Shell");
+
+        assert_eq!(
+            Solver::new().parse_item(&text).unwrap().base,
+            "Warlord Cuirass"
+        );
+    }
+
+    #[test]
+    fn finds_the_base_within_a_magic_item_name() {
+        let text = "Item Class: Body Armours
+Rarity: Magic
+Hale Warlord Cuirass of the Whale
+--------
+Item Level: 82
+";
+
+        assert_eq!(
+            Solver::new().parse_item(text).unwrap().base,
+            "Warlord Cuirass"
+        );
+    }
+
+    #[test]
+    fn parses_tiers_from_the_class_of_the_base() {
+        let solver = Solver::new();
+        let tier_of = |base: &str| {
+            let text = format!(
+                "Item Class: Unknown\nRarity: Magic\n{base}\n--------\nItem Level: 82\n--------\n18% increased Armour\n"
+            );
+            let item = solver.parse_item(&text).unwrap();
+            assert_eq!(ids(&item.prefixes), vec![("armor", 18)]);
+            item.prefixes[0].tier
+        };
+
+        assert_eq!(tier_of("Warlord Cuirass"), 8);
+        assert_eq!(tier_of("Conqueror Plate"), 9);
+        assert_eq!(tier_of("Emerald"), 1);
+    }
+
+    #[test]
+    fn builds_modifiers_from_the_tiers_of_a_class() {
+        let solver = Solver::new();
+
+        assert_eq!(Modifier::from_value(&solver, "chest", "armor", 18).tier, 8);
+        assert_eq!(
+            Modifier::from_value(&solver, "str-chest", "armor", 18).tier,
+            9
+        );
+        assert_eq!(Modifier::from_value(&solver, "jewel", "armor", 18).tier, 1);
+        assert_eq!(Modifier::from_tier(&solver, "chest", "armor", 1).value, 101);
+        assert_eq!(
+            Modifier::from_tier(&solver, "str-chest", "armor", 1).value,
+            111
+        );
+        assert_eq!(Modifier::from_tier(&solver, "jewel", "armor", 1).value, 16);
+    }
+
+    #[test]
+    fn formatted_item_text_parses_back_to_the_same_item() {
+        let solver = Solver::new();
+        for text in [COPIED_ITEM, COPIED_ADVANCED_ITEM] {
+            let mut item = solver.parse_item(text).unwrap();
+            item.unrevealed_affix = Some(AffixSide::Prefix);
+            item.set_next_action(Some("reveal".to_owned()));
+
+            let formatted = solver.format_item(&item);
+
+            assert_eq!(solver.parse_item(&formatted), Ok(item));
+        }
+    }
+
+    #[test]
+    fn formatted_tiers_are_numbered_from_one() {
+        let solver = Solver::new();
+        let item = solver.parse_item(COPIED_ADVANCED_ITEM).unwrap();
+
+        assert_eq!(item.prefixes[0].tier, 1);
+        assert!(
+            solver
+                .format_item(&item)
+                .contains("Prefix Modifier (Tier: 1)")
+        );
+    }
+}
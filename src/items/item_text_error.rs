@@ -0,0 +1,31 @@
+use std::fmt;
+
+/// An error while parsing game item text into an item state.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemTextError {
+    MissingRarity,
+    UnknownRarity(String),
+    UnknownBase,
+    MissingItemLevel,
+    InvalidItemLevel(String),
+    UnknownAffixSide(String),
+    UnknownModifier(String),
+}
+
+impl fmt::Display for ItemTextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingRarity => write!(f, "item text has no \"Rarity:\" line"),
+            Self::UnknownRarity(rarity) => write!(f, "rarity `{rarity}` is not known"),
+            Self::UnknownBase => write!(f, "item text has no known item base"),
+            Self::MissingItemLevel => write!(f, "item text has no \"Item Level:\" line"),
+            Self::InvalidItemLevel(level) => write!(f, "item level `{level}` is not valid"),
+            Self::UnknownAffixSide(affix) => {
+                write!(f, "modifier `{affix}` is neither a prefix nor a suffix")
+            }
+            Self::UnknownModifier(line) => write!(f, "modifier line `{line}` is not known"),
+        }
+    }
+}
+
+impl std::error::Error for ItemTextError {}
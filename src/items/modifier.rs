@@ -1,38 +1,68 @@
-use crate::crafting::{crafter::Crafter, solver::Solver};
+use crate::{
+    crafting::{crafter::Crafter, solver::Solver},
+    files::versioned::Versioned,
+};
+use serde_derive::{Deserialize, Serialize};
 
 /// Represents a single modifier on an item.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Modifier {
     pub name: String,
     pub id: String,
     pub tier: u8,
     pub value: u16,
     pub weight: u16,
+    pub desecrated: bool,
+    pub fractured: bool,
 }
 
 impl Modifier {
-    /// Makes a new [`Modifier`] from `value`.
-    pub fn from_value(solver: &Solver, id: &'static str, value: u16) -> Self {
-        let class_tiers = &solver.class_tiers.class_tiers;
-        let tier = solver.get_affix_tier(class_tiers, id.to_string(), value);
+    /// Makes a new [`Modifier`] from `value`, using the tiers of an item
+    /// `class`.
+    pub fn from_value(solver: &Solver, class: &str, id: &'static str, value: u16) -> Self {
+        let class_tier = solver.get_class_tier(class).expect("Unknown item class");
+        let tier = solver.get_affix_tier(class_tier, id, value);
         Self {
             name: String::new(),
             id: id.to_owned(),
             tier,
             value,
-            weight: solver.get_affix_tier_weight(class_tiers, id.to_string(), tier),
+            weight: solver.get_affix_tier_weight(class_tier, id, tier),
+            desecrated: false,
+            fractured: false,
         }
     }
 
-    /// Makes a new [`Modifier`] from `tier`.
-    pub fn from_tier(solver: &Solver, id: &'static str, tier: u8) -> Self {
-        let class_tiers = &solver.class_tiers.class_tiers;
+    /// Makes a new [`Modifier`] from `tier`, where `1` is the best tier,
+    /// using the tiers of an item `class`.
+    pub fn from_tier(solver: &Solver, class: &str, id: &'static str, tier: u8) -> Self {
+        let class_tier = solver.get_class_tier(class).expect("Unknown item class");
         Self {
             name: String::new(),
             id: id.to_owned(),
             tier,
-            value: solver.get_minimum_affix_value(class_tiers, id.to_string(), tier),
-            weight: solver.get_affix_tier_weight(class_tiers, id.to_string(), tier),
+            value: solver.get_minimum_affix_value(class_tier, id, tier),
+            weight: solver.get_affix_tier_weight(class_tier, id, tier),
+            desecrated: false,
+            fractured: false,
         }
     }
+
+    /// Makes this modifier fractured, so crafting can never remove it.
+    pub fn as_fractured(mut self) -> Self {
+        self.fractured = true;
+        self
+    }
+
+    /// Gets a display label for desecrated modifiers, defaults to an empty string.
+    pub fn get_desecrated_label(&self) -> &str {
+        if self.desecrated { " (desecrated)" } else { "" }
+    }
+
+    /// Gets a display label for fractured modifiers, defaults to an empty string.
+    pub fn get_fractured_label(&self) -> &str {
+        if self.fractured { " (fractured)" } else { "" }
+    }
 }
+
+impl Versioned for Modifier {}
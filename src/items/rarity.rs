@@ -0,0 +1,41 @@
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+/// Represents the rarity of an item.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Rarity {
+    #[default]
+    Normal,
+    Magic,
+    Rare,
+}
+
+impl Rarity {
+    /// Gets a rarity by `name`, e.g.: "rare".
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "normal" => Some(Self::Normal),
+            "magic" => Some(Self::Magic),
+            "rare" => Some(Self::Rare),
+            _ => None,
+        }
+    }
+
+    /// Gets the name of the rarity, e.g.: "rare".
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Magic => "magic",
+            Self::Rare => "rare",
+        }
+    }
+}
+
+impl fmt::Display for Rarity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.get_name())
+    }
+}
@@ -1,42 +1,95 @@
 /// Contains all crafting-related behaviors
 pub mod crafting {
-    pub mod affix_candidate;
+    pub mod action_recommendation;
+    pub mod affix_table;
+    pub mod best_cost;
+    pub mod budget_report;
+    pub mod cancellation_token;
+    pub mod compiled_affix;
+    pub mod cost_distribution;
+    pub mod craft_plan;
+    pub mod craft_step;
     pub mod crafter;
+    pub mod crafting_index;
+    pub mod crafting_session;
+    pub mod recombine_outcome;
+    pub mod recombine_plan;
     pub mod recombiner;
+    pub mod simulation_aggregate;
+    pub mod simulation_report;
+    pub mod simulation_run;
+    pub mod solve_budget;
+    pub mod solve_limits;
     pub mod solver;
+    pub mod solver_observer;
+    pub mod starting_item;
+    pub mod worker_pool;
 }
 
 /// Contains data entities populated by file
 pub mod datasets {
     pub mod affix;
     pub mod affix_range;
+    pub mod affix_slots;
     pub mod affix_tier;
     pub mod class;
     pub mod class_tier;
     pub mod class_tiers;
+    pub mod consumed_currency;
     pub mod craft_action;
     pub mod craft_actions;
     pub mod craft_outcome;
     pub mod hybrid_modifier_tier;
     pub mod item;
     pub mod items;
+    pub mod modifier_count;
     pub mod modifier_tier;
     pub mod modifiers;
+    pub mod outcome_action;
+    pub mod outcome_affix;
+    pub mod price;
+    pub mod prices;
+    pub mod recombination;
+    pub mod recombinations;
+    pub mod recombine_base;
+    pub mod recombine_item_level;
     pub mod stat;
+    pub mod valuation;
+    pub mod valuations;
+    pub mod valued_modifier;
 }
 
 /// Contains all file-related behaviors
 pub mod files {
     pub mod from_file;
+    pub mod infinite_float;
+    pub mod schema_error;
+    pub mod versioned;
+}
+
+/// Contains all interactive behaviors
+pub mod interactive {
+    pub mod dashboard;
+    pub mod repl;
 }
 
 /// Contains all item-related entities and behaviors
 pub mod items {
+    pub mod affix_side;
+    pub mod compact_item_state;
+    pub mod compact_modifier;
+    pub mod id_interner;
+    pub mod id_registry;
+    pub mod interned_id;
     pub mod item_state;
+    pub mod item_text;
+    pub mod item_text_error;
     pub mod modifier;
+    pub mod rarity;
 }
 
 /// Contains all random-related behaviors.
 pub mod random {
+    pub mod alias_table;
     pub mod randomizer;
 }
@@ -1,49 +1,179 @@
 use craft_solver::{
-    crafting::solver::Solver,
-    items::{item_state::ItemState, modifier::Modifier},
+    crafting::{solver::Solver, starting_item::StartingItem},
+    files::versioned::Versioned,
+    interactive::{dashboard::Dashboard, repl},
+    items::{item_state::ItemState, modifier::Modifier, rarity::Rarity},
 };
-use logger::log_severity::LogSeverity;
+use logger::{log_error, log_info, log_severity::LogSeverity};
+use std::sync::Arc;
 
 fn main() {
-    // show any important logs by log severity level
-    logger::set_logging_severity(LogSeverity::Info);
+    // show any important logs by log severity level, keeping the dashboard clear
+    let show_dashboard = std::env::args().any(|arg| arg.eq("--dashboard"));
+    logger::set_logging_severity(if show_dashboard {
+        LogSeverity::Warning
+    } else {
+        LogSeverity::Info
+    });
 
-    // set up the solver, which runs the crafting actions
-    let solver = Solver::new();
+    // set up the solver, which runs the crafting actions and reports costs in divines
+    let mut solver = Solver::new();
+    solver.report_currency = "divine".to_owned();
+
+    // look up the most affixes a rare and a magic base can have
+    let rare_slots = solver
+        .items
+        .get_affix_slots("Warlord Cuirass", Rarity::Rare);
+    let magic_slots = solver
+        .items
+        .get_affix_slots("Warlord Cuirass", Rarity::Magic);
 
     // define the end state
     let target_state = ItemState::new(
         "Warlord Cuirass",
         "chest",
-        "rare",
+        rare_slots,
         82,
-        vec![Modifier::from_value(&solver, "max-life", 180)],
+        vec![Modifier::from_value(&solver, "chest", "max-life", 180)],
         vec![
-            Modifier::from_tier(&solver, "chaos-res", 1),
-            Modifier::from_tier(&solver, "fire-res", 2),
+            Modifier::from_tier(&solver, "chest", "chaos-res", 2),
+            Modifier::from_tier(&solver, "chest", "fire-res", 3),
         ],
     );
 
+    // offer a magic base with high life for sale, to start crafting from
+    let price = solver.prices.to_base_currency(1.0, "divine");
+    solver.starting_items.push(StartingItem::new(
+        ItemState::new(
+            "Warlord Cuirass",
+            "chest",
+            magic_slots,
+            82,
+            vec![Modifier::from_value(&solver, "chest", "max-life", 180)],
+            vec![],
+        ),
+        price,
+    ));
+
+    // practice crafting towards the target in a sandbox, if asked to
+    if std::env::args().any(|arg| arg.eq("--interactive")) {
+        repl::run(&solver, target_state);
+        return;
+    }
+
+    // watch a simulation on a live dashboard, if asked to
+    if show_dashboard {
+        solver.observers.push(Arc::new(Dashboard::new(
+            solver.prices.clone(),
+            &solver.report_currency,
+        )));
+        solver.simulate(&target_state, 1000, 20);
+        return;
+    }
+
     // =======================
     // TEST RECOMBINATOR STUFF
     // =======================
     let right_item = ItemState::new(
         "Warlord Cuirass",
         "chest",
-        "rare",
+        rare_slots,
         82,
-        vec![Modifier::from_value(&solver, "armor", 100)],
-        vec![Modifier::from_tier(&solver, "cold-res", 2)],
+        vec![Modifier::from_value(&solver, "chest", "armor", 100)],
+        vec![Modifier::from_tier(&solver, "chest", "cold-res", 3)],
     );
 
-    let left_mods = vec![Modifier::from_value(&solver, "max-life", 180)];
-    let right_mods = vec![Modifier::from_value(&solver, "armor", 100)];
+    let left_mods = vec![Modifier::from_value(&solver, "chest", "max-life", 180)];
+    let right_mods = vec![Modifier::from_value(&solver, "chest", "armor", 100)];
 
-    solver.recombine(&target_state, &right_item, left_mods, right_mods);
+    for outcome in
+        solver.get_recombine_outcomes(&target_state, &right_item, &left_mods, &right_mods)
+    {
+        log_info!("{}", outcome.describe());
+    }
+
+    if let Some(recombined_item) =
+        solver.recombine(&target_state, &right_item, left_mods, right_mods)
+    {
+        recombined_item.display();
+    }
+
+    // plan which owned items to recombine, and which mods to carry
+    let prefix_item = ItemState::new(
+        "Warlord Cuirass",
+        "chest",
+        rare_slots,
+        82,
+        vec![
+            Modifier::from_value(&solver, "chest", "max-life", 180),
+            Modifier::from_value(&solver, "chest", "armor", 100),
+        ],
+        vec![Modifier::from_tier(&solver, "chest", "cold-res", 3)],
+    );
+    let suffix_item = ItemState::new(
+        "Warlord Cuirass",
+        "chest",
+        rare_slots,
+        82,
+        vec![Modifier::from_value(&solver, "chest", "flat-armor", 200)],
+        vec![
+            Modifier::from_tier(&solver, "chest", "chaos-res", 2),
+            Modifier::from_tier(&solver, "chest", "fire-res", 3),
+        ],
+    );
+
+    if let Some(plan) = solver.plan_recombination(&target_state, &[prefix_item, suffix_item]) {
+        plan.display();
+    }
     // ===========================
     // END TEST RECOMBINATOR STUFF
     // ===========================
 
+    // parse an item copied from the game with Ctrl+C
+    let copied_text = "Item Class: Body Armours
+Rarity: Magic
+Hale Warlord Cuirass
+--------
+Item Level: 82
+--------
++180(175-189) to maximum Life (fractured)
+";
+    match solver.parse_item(copied_text) {
+        Ok(copied_item) => {
+            // export it back as game-style text and JSON
+            log_info!("{}", solver.format_item(&copied_item));
+            log_info!("{}", copied_item.to_json());
+        }
+        Err(error) => log_error!("could not parse the copied item: {error}"),
+    }
+
+    // recommend what to do next with an item being held right now
+    let current_item = ItemState::new(
+        "Warlord Cuirass",
+        "chest",
+        magic_slots,
+        82,
+        vec![Modifier::from_value(&solver, "chest", "max-life", 180).as_fractured()],
+        vec![Modifier::from_tier(&solver, "chest", "chaos-res", 2)],
+    );
+    if let Some(recommendation) = solver
+        .recommend_next_actions(&current_item, &target_state, 100, 20)
+        .first()
+    {
+        recommendation.display(&solver.prices, &solver.report_currency);
+    }
+
     // run the simulation
     solver.simulate(&target_state, 100, 20);
+
+    // solve for the best chance at the target within a budget of 50 divines
+    let budget = solver.prices.to_base_currency(50.0, "divine");
+    solver
+        .solve_within_budget(&target_state, budget, 100, 20)
+        .display(&solver.prices, &solver.report_currency);
+
+    // plan the target, considering crafting two halves and recombining them
+    solver
+        .plan_craft(&target_state, 100, 20, 1)
+        .display(&solver.prices, &solver.report_currency);
 }
@@ -0,0 +1,89 @@
+use rand::Rng;
+
+/// A weighted distribution that samples in constant time, using Vose's
+/// alias method.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AliasTable {
+    probabilities: Vec<f64>,
+    aliases: Vec<usize>,
+}
+
+impl AliasTable {
+    /// Makes a new [`AliasTable`] from `weights`, or `None` if there is
+    /// nothing to sample.
+    pub fn new(weights: &[u32]) -> Option<Self> {
+        let total = weights.iter().map(|w| *w as f64).sum::<f64>();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let count = weights.len();
+        let mut probabilities = weights
+            .iter()
+            .map(|w| *w as f64 * count as f64 / total)
+            .collect::<Vec<f64>>();
+        let mut aliases = vec![0; count];
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..count).partition(|i| probabilities[*i] < 1.0);
+
+        while let (Some(less), Some(more)) = (small.pop(), large.pop()) {
+            aliases[less] = more;
+            probabilities[more] -= 1.0 - probabilities[less];
+            if probabilities[more] < 1.0 {
+                small.push(more);
+            } else {
+                large.push(more);
+            }
+        }
+        // anything left over is only off by rounding
+        for i in small.into_iter().chain(large) {
+            probabilities[i] = 1.0;
+        }
+
+        Some(Self {
+            probabilities,
+            aliases,
+        })
+    }
+
+    /// Samples a random index, weighted by the weights of the table.
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> usize {
+        let i = rng.random_range(0..self.probabilities.len());
+        if rng.random_bool(self.probabilities[i]) {
+            i
+        } else {
+            self.aliases[i]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn samples_reproduce_the_weights() {
+        let weights = [1, 2, 3, 4, 0];
+        let table = AliasTable::new(&weights).unwrap();
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let samples = 200_000;
+        let mut counts = [0; 5];
+        for _ in 0..samples {
+            counts[table.sample(&mut rng)] += 1;
+        }
+
+        for (count, weight) in counts.iter().zip(weights) {
+            let expected = weight as f64 / 10.0;
+            assert!((*count as f64 / samples as f64 - expected).abs() < 0.01);
+        }
+        assert_eq!(counts[4], 0);
+    }
+
+    #[test]
+    fn has_nothing_to_sample_without_weights() {
+        assert!(AliasTable::new(&[]).is_none());
+        assert!(AliasTable::new(&[0, 0]).is_none());
+    }
+}